@@ -1,18 +1,98 @@
 #![no_std]
 
 use soroban_sdk::{
-    Address, Env, Map, String, Symbol, Vec, contract, contracterror, contractevent, contractimpl,
-    contracttype, symbol_short, token,
+    Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec, contract,
+    contracterror, contractevent, contractimpl, contracttype, symbol_short, token, xdr::ToXdr,
 };
 
-// --- Storage Keys ---
-const KEY_ADMIN: Symbol = symbol_short!("admin");
-const KEY_TOTAL_LOCKED: Symbol = symbol_short!("locked");
-const KEY_PKG_COUNTER: Symbol = symbol_short!("pkg_cnt");
-const KEY_CONFIG: Symbol = symbol_short!("config");
-const KEY_PKG_IDX: Symbol = symbol_short!("pkg_idx"); // Aggregation index counter
-const KEY_DISTRIBUTORS: Symbol = symbol_short!("dstrbtrs"); // Map<Address, bool>
-const KEY_PAUSED: Symbol = symbol_short!("paused");
+// --- Storage ---
+
+/// Current storage schema version. See `migrate`.
+const SCHEMA_VERSION: u32 = 8;
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+enum DataKey {
+    // Instance storage
+    Version,
+    Admin,
+    PendingAdmin,
+    Config,
+    Paused,
+    TotalLocked, // Map<Address, i128>
+    PackageCounter,
+    PackageIndexCount, // Aggregation index counter
+    CampaignCounter,
+    Council,
+    ProposalCounter,
+    RoleMembers(Role), // Vec<Address>
+    TimelockDelay,
+    OperationCounter,
+    AggregatesRebuild,
+    Migration,
+    DistributionCounter,
+    // Persistent storage
+    Package(u64),
+    PackageIndex(u64), // index -> package id
+    Campaign(u64),
+    Proposal(u64),
+    Allowance(Address, Address), // (distributor, token)
+    Operation(u64),
+    PackageTtl(u64),
+    RecipientPackageCount(Address),
+    RecipientPackage(Address, u64), // (recipient, position) -> package id
+    Aggregates(Address),            // token -> Aggregates
+    VoucherSigner(Address),         // issuer -> BytesN<32> ed25519 public key
+    VoucherBudget(Address, Address), // (issuer, token) -> i128
+    VoucherSpent(Address, u64),     // (issuer, voucher id)
+    Distribution(u64),
+    DistributionClaims(u64, u32), // (distribution, index / 128) -> u128 bitmap word
+    PackageDelegate(u64),
+    RecipientDelegate(Address),
+    ClaimCommitment(u64, BytesN<32>), // (package, commitment) -> ledger committed at
+}
+
+// Schema v1 (the first release) stored ad-hoc symbol keys. These are only
+// read by `migrate`.
+const V1_KEY_ADMIN: Symbol = symbol_short!("admin");
+const V1_KEY_TOTAL_LOCKED: Symbol = symbol_short!("locked");
+const V1_KEY_PKG_COUNTER: Symbol = symbol_short!("pkg_cnt");
+const V1_KEY_CONFIG: Symbol = symbol_short!("config");
+const V1_KEY_PKG_IDX: Symbol = symbol_short!("pkg_idx");
+const V1_KEY_DISTRIBUTORS: Symbol = symbol_short!("dstrbtrs");
+const V1_KEY_PAUSED: Symbol = symbol_short!("paused");
+const V1_KEY_PKG: Symbol = symbol_short!("pkg");
+const V1_KEY_PIDX: Symbol = symbol_short!("pidx");
+
+/// Upper bound on the timelock delay so a misconfiguration cannot freeze
+/// sensitive operations indefinitely.
+const MAX_TIMELOCK_DELAY: u64 = 30 * 24 * 60 * 60;
+
+// TTLs are measured in ledgers (~5s each).
+const DAY_IN_LEDGERS: u32 = 17_280;
+const INSTANCE_TTL_THRESHOLD: u32 = 7 * DAY_IN_LEDGERS;
+const INSTANCE_TTL_EXTEND: u32 = 30 * DAY_IN_LEDGERS;
+/// A package is re-extended once fewer than this many ledgers remain.
+const PACKAGE_TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+const PACKAGE_TTL_EXTEND: u32 = 120 * DAY_IN_LEDGERS;
+
+/// Highest ID a package can be created with explicitly. The IDs above it are
+/// left to batch numbering, so explicit IDs cannot run the batch counter out.
+const MAX_EXPLICIT_PACKAGE_ID: u64 = u64::MAX / 2;
+
+/// Maximum number of index positions `list_packages` scans per call.
+const MAX_PAGE_SIZE: u32 = 50;
+/// Maximum number of index positions `rebuild_aggregates` processes per call.
+const MAX_REBUILD_BATCH: u32 = 100;
+/// Maximum number of index positions `migrate` rewrites per call.
+const MAX_MIGRATION_BATCH: u32 = 50;
+
+/// Limits on `Package.metadata`, keeping package entries small.
+const MAX_METADATA_KEYS: u32 = 16;
+const MAX_METADATA_VALUE_LEN: u32 = 256;
+/// Reserved metadata key anchoring off-chain evidence for a package (photos,
+/// signed forms). Its value must be a hex-encoded SHA-256 digest.
+pub const METADATA_DOC_HASH: Symbol = symbol_short!("doc_hash");
 
 // --- Data Types ---
 
@@ -25,19 +105,196 @@ pub enum PackageStatus {
     Expired = 2,
     Cancelled = 3,
     Refunded = 4,
+    /// Part of the amount has been claimed; the rest is still claimable.
+    PartiallyClaimed = 5,
+}
+
+/// TTL bookkeeping for a package's persistent entries. Contracts cannot read
+/// ledger TTLs, so the contract records the ledger it last extended them to.
+/// Entries may live longer if extended from outside the contract.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackageTtl {
+    pub id: u64,
+    /// Position of the package in the aggregation index.
+    pub index: u64,
+    /// Position of the package in its recipient's index.
+    pub recipient_index: u64,
+    /// Ledger sequence the package entries are known to live until.
+    pub live_until: u32,
+}
+
+/// A page of `get_packages_near_archival` results.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackageTtlPage {
+    pub records: Vec<PackageTtl>,
+    /// Cursor for the next call, or `None` once the index is exhausted.
+    pub next_cursor: Option<u64>,
+}
+
+/// Linear release schedule. Nothing is claimable before `cliff`; from then
+/// the vested amount grows linearly from `start` until the full amount at
+/// `end`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct VestingSchedule {
+    pub start: u64,
+    pub cliff: u64,
+    pub end: u64,
+}
+
+/// Equal installments released at `start` and every `period` seconds after,
+/// `periods` times in total. Unclaimed installments carry over.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecurringSchedule {
+    pub start: u64,
+    pub period: u64,
+    pub periods: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum PackageKind {
+    /// Claimable in full as soon as it is created.
+    Standard,
+    /// Released gradually according to the schedule.
+    Vesting(VestingSchedule),
+    /// Released in installments; `amount` covers all of them.
+    Recurring(RecurringSchedule),
+    /// Claimable in full by whoever presents the preimage of this SHA-256
+    /// hash, after committing to a destination. Created without a recipient.
+    HashLocked(BytesN<32>),
 }
 
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
 pub struct Package {
     pub id: u64,
-    pub recipient: Address,
+    /// `None` for hash-locked packages until they are claimed.
+    pub recipient: Option<Address>,
     pub amount: i128,
+    /// Amount paid out so far. Equals `amount` once fully claimed.
+    pub claimed_amount: i128,
     pub token: Address,
     pub status: PackageStatus,
     pub created_at: u64,
     pub expires_at: u64,
     pub metadata: Map<Symbol, String>,
+    /// Campaign the package was created under, if any.
+    pub campaign_id: Option<u64>,
+    /// Admin or distributor that created the package.
+    pub created_by: Address,
+    pub kind: PackageKind,
+    /// Recipients the package was reassigned away from, oldest first.
+    pub previous_recipients: Vec<Address>,
+}
+
+impl Package {
+    /// Amount not yet paid out.
+    pub fn remaining(&self) -> i128 {
+        self.amount - self.claimed_amount
+    }
+
+    /// Whether the package can still be claimed (ignoring expiry).
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self.status,
+            PackageStatus::Created | PackageStatus::PartiallyClaimed
+        )
+    }
+
+    /// Amount released to the recipient by `now`, claimed or not.
+    pub fn vested(&self, now: u64) -> i128 {
+        match &self.kind {
+            PackageKind::Standard | PackageKind::HashLocked(_) => self.amount,
+            PackageKind::Vesting(schedule) => {
+                if now < schedule.cliff {
+                    0
+                } else if now >= schedule.end {
+                    self.amount
+                } else {
+                    self.amount * (now - schedule.start) as i128
+                        / (schedule.end - schedule.start) as i128
+                }
+            }
+            PackageKind::Recurring(schedule) => {
+                if now < schedule.start {
+                    return 0;
+                }
+                let released =
+                    ((now - schedule.start) / schedule.period + 1).min(schedule.periods as u64);
+                self.amount / schedule.periods as i128 * released as i128
+            }
+        }
+    }
+
+    /// Amount the recipient could claim at `now`, ignoring status and expiry.
+    pub fn available(&self, now: u64) -> i128 {
+        self.vested(now) - self.claimed_amount
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u32)]
+pub enum CampaignStatus {
+    Active = 0,
+    Closed = 1,
+}
+
+/// A crisis response with its own budget. Packages created under a campaign
+/// draw from the shared token pool but may not exceed the campaign budget.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Campaign {
+    pub id: u64,
+    pub name: String,
+    pub token: Address,
+    pub budget: i128,
+    pub starts_at: u64,
+    /// 0 means the campaign has no end date.
+    pub ends_at: u64,
+    pub status: CampaignStatus,
+    /// Amount currently locked in `Created` packages of this campaign.
+    pub locked: i128,
+    /// Amount paid out to recipients from this campaign.
+    pub spent: i128,
+}
+
+/// Roles the admin can grant. Entrypoints open to a role also accept the
+/// admin, but `has_role` and `list_role_members` only report explicit grants.
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u32)]
+pub enum Role {
+    /// Can pause the contract (not unpause).
+    Pauser = 0,
+    /// Can refund expired or cancelled packages back to the admin.
+    Refunder = 1,
+    /// Can update `Config`.
+    ConfigManager = 2,
+    /// Reporting and maintenance tooling (keepers). Can extend storage TTLs
+    /// and rebuild aggregates, but not move funds or change settings.
+    Auditor = 3,
+    /// Can create packages.
+    Distributor = 4,
+}
+
+/// Per-token budget the admin assigns to a distributor. Package creation
+/// consumes `remaining`; revoking or cancelling the distributor's packages
+/// restores it.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistributorAllowance {
+    pub remaining: i128,
+    /// Amount `remaining` is reset to at the start of every refill period.
+    pub refill_amount: i128,
+    /// Refill period in seconds. 0 disables refills.
+    pub refill_period: u64,
+    /// Start of the current refill period.
+    pub period_start: u64,
 }
 
 #[contracttype]
@@ -48,12 +305,257 @@ pub struct Config {
     pub allowed_tokens: Vec<Address>,
 }
 
+/// M-of-N signer set that replaces the single admin key for privileged
+/// operations once configured.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Council {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// Privileged operations that go through council proposals.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum CouncilAction {
+    Disburse(u64),
+    Refund(u64),
+    /// (to, amount, token)
+    WithdrawSurplus(Address, i128, Address),
+    SetConfig(Config),
+    Pause,
+    Unpause,
+    AddSigner(Address),
+    RemoveSigner(Address),
+    SetThreshold(u32),
+    Upgrade(BytesN<32>),
+    GrantRole(Role, Address),
+    RevokeRole(Role, Address),
+    /// (distributor, token, amount, refill_amount, refill_period)
+    SetAllowance(Address, Address, i128, i128, u64),
+    /// (id, new_recipient, reason, with_consent)
+    ReassignRecipient(u64, Address, String, bool),
+    /// (new_admin, expires_at)
+    ProposeAdmin(Address, u64),
+    CancelAdminTransfer,
+    BatchDisburse(Vec<u64>),
+    BatchRefund(Vec<u64>),
+    Revoke(u64),
+    Cancel(u64),
+    BatchRevoke(Vec<u64>),
+    BatchCancel(Vec<u64>),
+    SetTimelockDelay(u64),
+    CancelOperation(u64),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u32)]
+pub enum ProposalStatus {
+    Pending = 0,
+    Executed = 1,
+    Cancelled = 2,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Address,
+    pub action: CouncilAction,
+    pub approvals: Vec<Address>,
+    pub status: ProposalStatus,
+    pub created_at: u64,
+}
+
+/// Admin transfer awaiting acceptance by the proposed admin.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub proposed_at: u64,
+    /// 0 means the proposal does not expire.
+    pub expires_at: u64,
+}
+
+/// Sensitive operations that must wait out the timelock delay once enabled.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimelockOp {
+    /// (to, amount, token)
+    WithdrawSurplus(Address, i128, Address),
+    SetConfig(Config),
+    RemoveDistributor(Address),
+    SetDelay(u64),
+    Upgrade(BytesN<32>),
+    /// (distributor, token, amount, refill_amount, refill_period)
+    SetAllowance(Address, Address, i128, i128, u64),
+    /// (id, new_recipient, reason); reassigns without the recipient's consent
+    ReassignRecipient(u64, Address, String),
+    /// Refunds every listed package to the admin, all or nothing.
+    Refund(Vec<u64>),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u32)]
+pub enum OperationStatus {
+    Queued = 0,
+    Executed = 1,
+    Cancelled = 2,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueuedOperation {
+    pub id: u64,
+    pub op: TimelockOp,
+    pub queued_at: u64,
+    /// Earliest timestamp at which the operation can be executed.
+    pub eta: u64,
+    pub status: OperationStatus,
+}
+
+/// Criteria for `list_packages`. Unset fields and an empty `statuses` match
+/// everything; time bounds are inclusive.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackageFilter {
+    pub statuses: Vec<PackageStatus>,
+    pub token: Option<Address>,
+    pub recipient: Option<Address>,
+    pub created_from: Option<u64>,
+    pub created_to: Option<u64>,
+    pub expires_from: Option<u64>,
+    pub expires_to: Option<u64>,
+}
+
+/// A payment authorized off-chain by the voucher signer. The signature
+/// covers the XDR of `(contract address, voucher)`, so a voucher is only
+/// valid for the contract it was issued for.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Voucher {
+    /// Unique per issuer; different issuers may reuse the same ids.
+    pub id: u64,
+    /// Operator whose signer key signs the voucher and whose budget pays it.
+    pub issuer: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    /// Timestamp after which the voucher can no longer be redeemed; 0 never expires.
+    pub expires_at: u64,
+}
+
+/// A bulk distribution committed to as a Merkle root instead of individual
+/// packages. Leaves are `sha256(xdr((index, recipient, amount)))`; each
+/// parent is the sha256 of its two children concatenated in ascending order.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Distribution {
+    pub id: u64,
+    pub token: Address,
+    pub merkle_root: BytesN<32>,
+    /// Amount locked for the distribution; claims can never exceed it.
+    pub total: i128,
+    pub claimed: i128,
+    pub expires_at: u64,
+    pub created_by: Address,
+    /// Set once the unclaimed remainder has been returned to the pool.
+    pub closed: bool,
+}
+
+/// Permission for `delegate` to claim on the recipient's behalf.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClaimDelegation {
+    pub recipient: Address,
+    pub delegate: Address,
+    /// Pay claimed funds to the delegate instead of the recipient.
+    pub pay_delegate: bool,
+}
+
+/// Outcome of one item in a batch operation.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum BatchOutcome {
+    /// Succeeded, moving this amount.
+    Ok(i128),
+    /// Failed with this `Error` code; the item was left unchanged.
+    Failed(u32),
+}
+
+/// Result of one row of `batch_create_best_effort`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum BatchCreateResult {
+    Created(u64),
+    /// Skipped with this `Error` code.
+    Failed(u32),
+}
+
+/// What `validate_package` and `validate_batch` found for one package.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackageDiagnostics {
+    /// The ID the package was checked under.
+    pub id: u64,
+    /// `Error` codes of every check that failed; empty if creation would
+    /// succeed.
+    pub errors: Vec<u32>,
+}
+
+/// What `validate_batch` found for a batch.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchDiagnostics {
+    /// `Error` codes that fail the whole batch.
+    pub errors: Vec<u32>,
+    /// One entry per row, in order.
+    pub rows: Vec<PackageDiagnostics>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchItemResult {
+    pub id: u64,
+    pub outcome: BatchOutcome,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackagePage {
+    pub packages: Vec<Package>,
+    /// Cursor for the next call, or `None` once the index is exhausted.
+    pub next_cursor: Option<u64>,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
 pub struct Aggregates {
     pub total_committed: i128,
     pub total_claimed: i128,
     pub total_expired_cancelled: i128,
+    pub committed_count: u64,
+    pub claimed_count: u64,
+    pub expired_cancelled_count: u64,
+}
+
+/// Progress of `migrate` over the v1 index positions.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+struct MigrationProgress {
+    next: u64,
+    end: u64,
+}
+
+/// Progress of `rebuild_aggregates` over the index positions that existed
+/// when the deployment was migrated.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+struct AggregatesRebuild {
+    next: u64,
+    end: u64,
 }
 
 #[contracterror]
@@ -74,6 +576,37 @@ pub enum Error {
     MismatchedArrays = 12,
     InsufficientSurplus = 13,
     ContractPaused = 14,
+    CampaignNotFound = 15,
+    CampaignNotActive = 16,
+    CampaignBudgetExceeded = 17,
+    // privileged operation must go through a council proposal
+    CouncilRequired = 18,
+    CouncilNotSet = 19,
+    InvalidThreshold = 20,
+    ProposalNotFound = 21,
+    ProposalNotPending = 22,
+    AlreadyApproved = 23,
+    ThresholdNotMet = 24,
+    NoPendingAdmin = 25,
+    AdminTransferExpired = 26,
+    AllowanceExceeded = 27,
+    // operation must be scheduled through the timelock queue
+    TimelockRequired = 28,
+    OperationNotFound = 29,
+    OperationNotQueued = 30,
+    TimelockNotReady = 31,
+    NothingToClaim = 32,
+    InvalidSecret = 33,
+    VoucherSpent = 34,
+    DistributionNotFound = 35,
+    InvalidProof = 36,
+    AlreadyClaimed = 37,
+    InvalidMetadata = 38,
+    MetadataLimitExceeded = 39,
+    // explicit ID above `MAX_EXPLICIT_PACKAGE_ID`, or no batch IDs left
+    PackageIdOutOfRange = 40,
+    // no claim commitment recorded in an earlier ledger
+    ClaimNotCommitted = 41,
 }
 
 // --- Contract Events ---
@@ -89,7 +622,7 @@ pub struct FundEvent {
 #[contractevent]
 pub struct PackageCreatedEvent {
     pub id: u64,
-    pub recipient: Address,
+    pub recipient: Option<Address>,
     pub amount: i128,
 }
 
@@ -97,6 +630,8 @@ pub struct PackageCreatedEvent {
 pub struct ClaimedEvent {
     pub id: u64,
     pub recipient: Address,
+    /// Address the funds were sent to; the recipient unless redirected.
+    pub destination: Address,
     pub amount: i128,
 }
 
@@ -153,533 +688,3587 @@ pub struct ContractUnpausedEvent {
     pub admin: Address,
 }
 
-#[contract]
-pub struct AidEscrow;
+#[contractevent]
+pub struct CampaignCreatedEvent {
+    pub id: u64,
+    pub token: Address,
+    pub budget: i128,
+}
 
-#[contractimpl]
-impl AidEscrow {
-    // --- Admin & Config ---
+#[contractevent]
+pub struct CampaignClosedEvent {
+    pub id: u64,
+    pub admin: Address,
+}
 
-    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
-        if env.storage().instance().has(&KEY_ADMIN) {
-            return Err(Error::AlreadyInitialized);
-        }
-        env.storage().instance().set(&KEY_ADMIN, &admin);
-        let config = Config {
-            min_amount: 1,
-            max_expires_in: 0,
-            allowed_tokens: Vec::new(&env),
-        };
-        env.storage().instance().set(&KEY_CONFIG, &config);
-        Ok(())
-    }
+#[contractevent]
+pub struct CouncilUpdatedEvent {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
 
-    pub fn get_admin(env: Env) -> Result<Address, Error> {
-        env.storage()
-            .instance()
-            .get(&KEY_ADMIN)
-            .ok_or(Error::NotInitialized)
-    }
+#[contractevent]
+pub struct ProposalCreatedEvent {
+    pub id: u64,
+    pub proposer: Address,
+    pub action: CouncilAction,
+}
 
-    pub fn add_distributor(env: Env, addr: Address) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
+#[contractevent]
+pub struct ProposalApprovedEvent {
+    pub id: u64,
+    pub signer: Address,
+    pub approvals: u32,
+}
 
-        let mut distributors: Map<Address, bool> = env
-            .storage()
-            .instance()
-            .get(&KEY_DISTRIBUTORS)
-            .unwrap_or(Map::new(&env));
-        distributors.set(addr, true);
-        env.storage()
-            .instance()
-            .set(&KEY_DISTRIBUTORS, &distributors);
+#[contractevent]
+pub struct ProposalExecutedEvent {
+    pub id: u64,
+}
 
-        Ok(())
-    }
+#[contractevent]
+pub struct ProposalCancelledEvent {
+    pub id: u64,
+    pub signer: Address,
+}
 
-    pub fn remove_distributor(env: Env, addr: Address) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
+#[contractevent]
+pub struct AdminTransferProposedEvent {
+    pub admin: Address,
+    pub new_admin: Address,
+    pub expires_at: u64,
+}
 
-        let mut distributors: Map<Address, bool> = env
-            .storage()
-            .instance()
-            .get(&KEY_DISTRIBUTORS)
-            .unwrap_or(Map::new(&env));
-        distributors.remove(addr);
-        env.storage()
-            .instance()
-            .set(&KEY_DISTRIBUTORS, &distributors);
+#[contractevent]
+pub struct AdminTransferCancelledEvent {
+    pub admin: Address,
+    pub new_admin: Address,
+}
 
-        Ok(())
-    }
+#[contractevent]
+pub struct AdminTransferredEvent {
+    pub old_admin: Address,
+    pub new_admin: Address,
+}
 
-    pub fn set_config(env: Env, config: Config) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
+#[contractevent]
+pub struct RoleGrantedEvent {
+    pub role: Role,
+    pub account: Address,
+    pub admin: Address,
+}
 
-        if config.min_amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
+#[contractevent]
+pub struct RoleRevokedEvent {
+    pub role: Role,
+    pub account: Address,
+    pub admin: Address,
+}
 
-        env.storage().instance().set(&KEY_CONFIG, &config);
-        Ok(())
-    }
+#[contractevent]
+pub struct AllowanceSetEvent {
+    pub distributor: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub refill_amount: i128,
+    pub refill_period: u64,
+}
 
-    pub fn pause(env: Env) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
-        env.storage().instance().set(&KEY_PAUSED, &true);
-        ContractPausedEvent { admin }.publish(&env);
-        Ok(())
-    }
+#[contractevent]
+pub struct OperationQueuedEvent {
+    pub id: u64,
+    pub op: TimelockOp,
+    pub eta: u64,
+}
 
-    pub fn unpause(env: Env) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
-        env.storage().instance().set(&KEY_PAUSED, &false);
-        ContractUnpausedEvent { admin }.publish(&env);
-        Ok(())
+#[contractevent]
+pub struct OperationCancelledEvent {
+    pub id: u64,
+    pub admin: Address,
+}
+
+#[contractevent]
+pub struct OperationExecutedEvent {
+    pub id: u64,
+}
+
+#[contractevent]
+pub struct ContractUpgradedEvent {
+    pub wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+pub struct MigratedEvent {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+#[contractevent]
+pub struct VoucherSignerSetEvent {
+    pub public_key: BytesN<32>,
+    pub operator: Address,
+}
+
+#[contractevent]
+pub struct VoucherBudgetChangedEvent {
+    pub issuer: Address,
+    pub token: Address,
+    pub budget: i128,
+}
+
+#[contractevent]
+pub struct VoucherRedeemedEvent {
+    pub id: u64,
+    pub issuer: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct DistributionCreatedEvent {
+    pub id: u64,
+    pub token: Address,
+    pub merkle_root: BytesN<32>,
+    pub total: i128,
+}
+
+#[contractevent]
+pub struct MerkleClaimedEvent {
+    pub id: u64,
+    pub index: u32,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct DistributionClosedEvent {
+    pub id: u64,
+    pub unclaimed: i128,
+}
+
+#[contractevent]
+pub struct RecipientReassignedEvent {
+    pub id: u64,
+    pub old_recipient: Address,
+    pub new_recipient: Address,
+    pub reason: String,
+    pub admin: Address,
+}
+
+#[contractevent]
+pub struct MetadataUpdatedEvent {
+    pub id: u64,
+    pub key: Symbol,
+    /// New value, or `None` when the key was removed.
+    pub value: Option<String>,
+    pub updated_by: Address,
+}
+
+#[contractevent]
+pub struct ClaimCommittedEvent {
+    pub id: u64,
+    pub commitment: BytesN<32>,
+}
+
+#[contractevent]
+pub struct DelegateSetEvent {
+    pub recipient: Address,
+    pub package_id: Option<u64>,
+    pub delegate: Address,
+    pub pay_delegate: bool,
+}
+
+#[contractevent]
+pub struct DelegateRevokedEvent {
+    pub recipient: Address,
+    pub package_id: Option<u64>,
+}
+
+#[contractevent]
+pub struct ClaimedOnBehalfEvent {
+    pub id: u64,
+    pub recipient: Address,
+    pub delegate: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct BatchProcessedEvent {
+    pub action: Symbol,
+    pub succeeded: u32,
+    pub failed: u32,
+    pub total_amount: i128,
+}
+
+/// Token movements collected while processing packages and applied once at
+/// the end of the call: a single `TotalLocked` write and a single transfer
+/// per (token, destination) pair.
+struct Settlement {
+    unlocked: Map<Address, i128>,
+    transfers: Map<(Address, Address), i128>,
+}
+
+impl Settlement {
+    fn new(env: &Env) -> Self {
+        Settlement {
+            unlocked: Map::new(env),
+            transfers: Map::new(env),
+        }
     }
 
-    pub fn is_paused(env: Env) -> bool {
-        env.storage().instance().get(&KEY_PAUSED).unwrap_or(false)
+    fn unlock(&mut self, token: &Address, amount: i128) {
+        let current = self.unlocked.get(token.clone()).unwrap_or(0);
+        self.unlocked.set(token.clone(), current + amount);
     }
 
-    pub fn get_config(env: Env) -> Config {
-        env.storage().instance().get(&KEY_CONFIG).unwrap_or(Config {
+    fn pay(&mut self, token: &Address, to: &Address, amount: i128) {
+        let key = (token.clone(), to.clone());
+        let current = self.transfers.get(key.clone()).unwrap_or(0);
+        self.transfers.set(key, current + amount);
+    }
+
+    fn apply(self, env: &Env) {
+        if !self.unlocked.is_empty() {
+            let mut locked_map: Map<Address, i128> = env
+                .storage()
+                .instance()
+                .get(&DataKey::TotalLocked)
+                .unwrap_or(Map::new(env));
+            for (token, amount) in self.unlocked.iter() {
+                let current = locked_map.get(token.clone()).unwrap_or(0);
+                locked_map.set(
+                    token,
+                    if current > amount {
+                        current - amount
+                    } else {
+                        0
+                    },
+                );
+            }
+            env.storage()
+                .instance()
+                .set(&DataKey::TotalLocked, &locked_map);
+        }
+
+        for ((token, to), amount) in self.transfers.iter() {
+            if amount > 0 {
+                token::Client::new(env, &token).transfer(
+                    &env.current_contract_address(),
+                    &to,
+                    &amount,
+                );
+            }
+        }
+    }
+}
+
+#[contract]
+pub struct AidEscrow;
+
+#[contractimpl]
+impl AidEscrow {
+    // --- Admin & Config ---
+
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        // A v1 deployment awaiting `migrate` still counts as initialized
+        if env.storage().instance().has(&DataKey::Admin)
+            || env.storage().instance().has(&V1_KEY_ADMIN)
+        {
+            return Err(Error::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::Version, &SCHEMA_VERSION);
+        let config = Config {
             min_amount: 1,
             max_expires_in: 0,
             allowed_tokens: Vec::new(&env),
-        })
+        };
+        env.storage().instance().set(&DataKey::Config, &config);
+        Self::bump_instance_ttl(&env);
+        Ok(())
     }
 
-    // --- Funding & Packages ---
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)
+    }
 
-    /// Funds the contract (Pool Model).
-    /// Transfers `amount` of `token` from `from` to this contract.
-    /// This increases the contract's balance, allowing new packages to be created.
-    pub fn fund(env: Env, token: Address, from: Address, amount: i128) -> Result<(), Error> {
-        if amount <= 0 {
-            return Err(Error::InvalidAmount);
+    /// Starts a two-step admin transfer. The new admin must call `accept_admin`
+    /// before `expires_at` (0 for no expiry). Proposing again replaces any
+    /// pending transfer. Goes through the council once one is configured.
+    pub fn propose_admin(env: Env, new_admin: Address, expires_at: u64) -> Result<(), Error> {
+        let admin = Self::require_sole_admin(&env)?;
+        Self::propose_admin_internal(&env, admin, new_admin, expires_at)
+    }
+
+    /// Completes a pending admin transfer. Requires the proposed admin's auth.
+    pub fn accept_admin(env: Env) -> Result<(), Error> {
+        let old_admin = Self::get_admin(env.clone())?;
+        let pending = Self::get_pending_admin(env.clone()).ok_or(Error::NoPendingAdmin)?;
+
+        if pending.expires_at > 0 && env.ledger().timestamp() > pending.expires_at {
+            return Err(Error::AdminTransferExpired);
         }
-        from.require_auth();
 
-        // Perform transfer: From -> Contract
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&from, env.current_contract_address(), &amount);
+        pending.new_admin.require_auth();
 
-        // Emit event
-        FundEvent {
-            from,
-            token,
-            amount,
+        env.storage()
+            .instance()
+            .set(&DataKey::Admin, &pending.new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        AdminTransferredEvent {
+            old_admin,
+            new_admin: pending.new_admin,
         }
         .publish(&env);
 
         Ok(())
     }
 
-    /// Creates a package with a specific ID.
-    /// Locks funds from the available pool (Contract Balance - Total Locked).
-    pub fn create_package(
+    /// Cancels a pending admin transfer. Goes through the council once one is
+    /// configured, so the outgoing key cannot block its own rotation.
+    pub fn cancel_admin_transfer(env: Env) -> Result<(), Error> {
+        let admin = Self::require_sole_admin(&env)?;
+        Self::cancel_admin_transfer_internal(&env, admin)
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    // --- Roles ---
+
+    /// Grants `role` to `account`. Goes through the council once one is
+    /// configured.
+    pub fn grant_role(env: Env, role: Role, account: Address) -> Result<(), Error> {
+        let admin = Self::require_sole_admin(&env)?;
+        Self::grant_role_internal(&env, admin, role, account);
+        Ok(())
+    }
+
+    /// Revokes `role` from `account`. Goes through the council once one is
+    /// configured. Removing a distributor is subject to the timelock once it
+    /// is enabled.
+    pub fn revoke_role(env: Env, role: Role, account: Address) -> Result<(), Error> {
+        let admin = Self::require_sole_admin(&env)?;
+
+        if role == Role::Distributor {
+            Self::check_timelock(&env)?;
+        }
+        Self::revoke_role_internal(&env, admin, role, account)
+    }
+
+    /// Whether `role` was granted to `account`. The admin is not reported as
+    /// a member unless explicitly granted the role.
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        Self::list_role_members(env, role).contains(account)
+    }
+
+    pub fn list_role_members(env: Env, role: Role) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::RoleMembers(role))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Shorthand for `grant_role(Role::Distributor, addr)`.
+    pub fn add_distributor(env: Env, addr: Address) -> Result<(), Error> {
+        Self::grant_role(env, Role::Distributor, addr)
+    }
+
+    /// Shorthand for `revoke_role(Role::Distributor, addr)`.
+    pub fn remove_distributor(env: Env, addr: Address) -> Result<(), Error> {
+        Self::revoke_role(env, Role::Distributor, addr)
+    }
+
+    /// Assigns `distributor` an allowance of `amount` in `token`. If
+    /// `refill_period` is non-zero the remaining allowance is reset to
+    /// `refill_amount` at the start of every period. The admin itself is not
+    /// subject to allowances unless the timelock is enabled. Goes through the
+    /// council once one is configured.
+    ///
+    /// With the timelock enabled, only lowering to a non-refilling amount at
+    /// most the current remaining allowance takes effect directly; anything
+    /// else must be queued as `TimelockOp::SetAllowance`.
+    pub fn set_distributor_allowance(
         env: Env,
-        operator: Address,
-        id: u64,
-        recipient: Address,
+        distributor: Address,
+        token: Address,
         amount: i128,
+        refill_amount: i128,
+        refill_period: u64,
+    ) -> Result<(), Error> {
+        Self::require_sole_admin(&env)?;
+        let remaining =
+            Self::get_distributor_allowance(env.clone(), distributor.clone(), token.clone())
+                .map_or(0, |allowance| allowance.remaining);
+        if refill_period > 0 || amount > remaining {
+            Self::check_timelock(&env)?;
+        }
+        Self::set_allowance_internal(
+            &env,
+            distributor,
+            token,
+            amount,
+            refill_amount,
+            refill_period,
+        )
+    }
+
+    /// Returns the distributor's allowance for `token` with any due refill applied.
+    pub fn get_distributor_allowance(
+        env: Env,
+        distributor: Address,
         token: Address,
-        expires_at: u64,
-    ) -> Result<u64, Error> {
-        Self::check_paused(&env)?;
-        Self::require_admin_or_distributor(&env, &operator)?;
-        let config = Self::get_config(env.clone());
+    ) -> Option<DistributorAllowance> {
+        let mut allowance: DistributorAllowance = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Allowance(distributor, token))?;
+
+        let now = env.ledger().timestamp();
+        if allowance.refill_period > 0 && now >= allowance.period_start + allowance.refill_period {
+            let elapsed = (now - allowance.period_start) / allowance.refill_period;
+            allowance.period_start += elapsed * allowance.refill_period;
+            allowance.remaining = allowance.refill_amount;
+        }
+
+        Some(allowance)
+    }
+
+    /// Updates the config. Callable by the admin or a `ConfigManager`.
+    pub fn set_config(env: Env, caller: Address, config: Config) -> Result<(), Error> {
+        Self::require_role_or_sole_admin(&env, &caller, Role::ConfigManager)?;
+        Self::check_timelock(&env)?;
+        Self::set_config_internal(&env, config)
+    }
+
+    /// Pauses the contract. Callable by the admin or a `Pauser`, so field staff
+    /// can stop activity in an emergency.
+    pub fn pause(env: Env, caller: Address) -> Result<(), Error> {
+        Self::require_role_or_sole_admin(&env, &caller, Role::Pauser)?;
+        Self::pause_internal(&env, caller)
+    }
+
+    /// Unpauses the contract. Admin only; pausers cannot resume activity.
+    pub fn unpause(env: Env) -> Result<(), Error> {
+        let admin = Self::require_sole_admin(&env)?;
+        Self::unpause_internal(&env, admin)
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    pub fn get_config(env: Env) -> Config {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config)
+            .unwrap_or(Config {
+                min_amount: 1,
+                max_expires_in: 0,
+                allowed_tokens: Vec::new(&env),
+            })
+    }
+
+    // --- Council ---
+
+    /// Configures the M-of-N council. Can only be called once, by the admin.
+    /// From then on `disburse`, `refund`, `revoke`, `cancel_package` and their
+    /// batch forms, `withdraw_surplus`, `set_config`, `pause`, `unpause`,
+    /// `grant_role`, `revoke_role`, `set_distributor_allowance`,
+    /// `reassign_recipient`, timelock changes and admin transfers must be
+    /// executed through council proposals, and signer changes themselves
+    /// require the threshold. The admin can no
+    /// longer create packages, vouchers or distributions; distributors
+    /// granted through the council keep doing so within their allowances.
+    pub fn set_council(env: Env, signers: Vec<Address>, threshold: u32) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        if env.storage().instance().has(&DataKey::Council) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        let mut unique: Vec<Address> = Vec::new(&env);
+        for signer in signers.iter() {
+            if unique.contains(signer.clone()) {
+                return Err(Error::InvalidState);
+            }
+            unique.push_back(signer);
+        }
+
+        Self::store_council(
+            &env,
+            Council {
+                signers: unique,
+                threshold,
+            },
+        )
+    }
+
+    pub fn get_council(env: Env) -> Result<Council, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Council)
+            .ok_or(Error::CouncilNotSet)
+    }
+
+    /// Opens a proposal for a privileged action. The proposer's approval is
+    /// counted immediately. Returns the proposal id.
+    pub fn propose(env: Env, proposer: Address, action: CouncilAction) -> Result<u64, Error> {
+        proposer.require_auth();
+        let council = Self::get_council(env.clone())?;
+        if !council.signers.contains(proposer.clone()) {
+            return Err(Error::NotAuthorized);
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ProposalCounter)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::ProposalCounter, &(id + 1));
+
+        let mut approvals = Vec::new(&env);
+        approvals.push_back(proposer.clone());
+        let proposal = Proposal {
+            id,
+            proposer: proposer.clone(),
+            action: action.clone(),
+            approvals,
+            status: ProposalStatus::Pending,
+            created_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Proposal(id), &proposal);
+
+        ProposalCreatedEvent {
+            id,
+            proposer,
+            action,
+        }
+        .publish(&env);
+
+        Ok(id)
+    }
+
+    pub fn approve(env: Env, signer: Address, proposal_id: u64) -> Result<(), Error> {
+        signer.require_auth();
+        let council = Self::get_council(env.clone())?;
+        if !council.signers.contains(signer.clone()) {
+            return Err(Error::NotAuthorized);
+        }
+
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id)?;
+        if proposal.status != ProposalStatus::Pending {
+            return Err(Error::ProposalNotPending);
+        }
+        if proposal.approvals.contains(signer.clone()) {
+            return Err(Error::AlreadyApproved);
+        }
+
+        proposal.approvals.push_back(signer.clone());
+        env.storage()
+            .persistent()
+            .set(&DataKey::Proposal(proposal_id), &proposal);
+
+        ProposalApprovedEvent {
+            id: proposal_id,
+            signer,
+            approvals: proposal.approvals.len(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Cancels a pending proposal. Any current signer may cancel.
+    pub fn cancel_proposal(env: Env, signer: Address, proposal_id: u64) -> Result<(), Error> {
+        signer.require_auth();
+        let council = Self::get_council(env.clone())?;
+        if !council.signers.contains(signer.clone()) {
+            return Err(Error::NotAuthorized);
+        }
+
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id)?;
+        if proposal.status != ProposalStatus::Pending {
+            return Err(Error::ProposalNotPending);
+        }
+
+        proposal.status = ProposalStatus::Cancelled;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Proposal(proposal_id), &proposal);
+
+        ProposalCancelledEvent {
+            id: proposal_id,
+            signer,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Executes a proposal once approvals from current signers reach the
+    /// threshold. Callable by anyone.
+    pub fn execute(env: Env, proposal_id: u64) -> Result<(), Error> {
+        let council = Self::get_council(env.clone())?;
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id)?;
+        if proposal.status != ProposalStatus::Pending {
+            return Err(Error::ProposalNotPending);
+        }
+
+        // Approvals from signers removed since approving no longer count
+        let mut approvals: u32 = 0;
+        for signer in proposal.approvals.iter() {
+            if council.signers.contains(signer) {
+                approvals += 1;
+            }
+        }
+        if approvals < council.threshold {
+            return Err(Error::ThresholdNotMet);
+        }
+
+        // Mark executed before running the action (Re-entrancy protection)
+        proposal.status = ProposalStatus::Executed;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Proposal(proposal_id), &proposal);
+
+        let admin = Self::get_admin(env.clone())?;
+        let timelocked = Self::get_timelock_delay(env.clone()) > 0;
+        match proposal.action {
+            CouncilAction::Disburse(id) => Self::settle(&env, |settlement| {
+                Self::disburse_internal(&env, settlement, admin, id)
+            })?,
+            CouncilAction::Refund(id) if timelocked => {
+                Self::queue_operation_internal(
+                    &env,
+                    TimelockOp::Refund(Vec::from_array(&env, [id])),
+                );
+            }
+            CouncilAction::Refund(id) => Self::settle(&env, |settlement| {
+                Self::refund_internal(&env, settlement, admin, id)
+            })?,
+            // Per-item outcomes are reported in the batch event
+            CouncilAction::BatchDisburse(ids) => {
+                Self::run_batch(&env, symbol_short!("disburse"), ids, |settlement, id| {
+                    Self::disburse_internal(&env, settlement, admin.clone(), id)
+                });
+            }
+            CouncilAction::BatchRefund(ids) if timelocked => {
+                Self::queue_operation_internal(&env, TimelockOp::Refund(ids));
+            }
+            CouncilAction::BatchRefund(ids) => {
+                Self::run_batch(&env, symbol_short!("refund"), ids, |settlement, id| {
+                    Self::refund_internal(&env, settlement, admin.clone(), id)
+                });
+            }
+            CouncilAction::Revoke(id) => Self::settle(&env, |settlement| {
+                Self::revoke_internal(&env, settlement, &admin, id)
+            })?,
+            CouncilAction::Cancel(id) => Self::settle(&env, |settlement| {
+                Self::cancel_internal(&env, settlement, &admin, id)
+            })?,
+            CouncilAction::BatchRevoke(ids) => {
+                Self::run_batch(&env, symbol_short!("revoke"), ids, |settlement, id| {
+                    Self::revoke_internal(&env, settlement, &admin, id)
+                });
+            }
+            CouncilAction::BatchCancel(ids) => {
+                Self::run_batch(&env, symbol_short!("cancel"), ids, |settlement, id| {
+                    Self::cancel_internal(&env, settlement, &admin, id)
+                });
+            }
+            CouncilAction::SetTimelockDelay(delay) if timelocked => {
+                Self::queue_operation_internal(&env, TimelockOp::SetDelay(delay));
+            }
+            CouncilAction::SetTimelockDelay(delay) => {
+                Self::set_timelock_delay_internal(&env, delay)?
+            }
+            CouncilAction::CancelOperation(op_id) => {
+                Self::cancel_operation_internal(&env, admin, op_id)?
+            }
+            // With the timelock enabled, approved sensitive actions are queued
+            CouncilAction::WithdrawSurplus(to, amount, token) if timelocked => {
+                Self::queue_operation_internal(
+                    &env,
+                    TimelockOp::WithdrawSurplus(to, amount, token),
+                );
+            }
+            CouncilAction::WithdrawSurplus(to, amount, token) => {
+                Self::withdraw_surplus_internal(&env, to, amount, token)?
+            }
+            CouncilAction::SetConfig(config) if timelocked => {
+                Self::queue_operation_internal(&env, TimelockOp::SetConfig(config));
+            }
+            CouncilAction::SetConfig(config) => Self::set_config_internal(&env, config)?,
+            CouncilAction::Upgrade(wasm_hash) if timelocked => {
+                Self::queue_operation_internal(&env, TimelockOp::Upgrade(wasm_hash));
+            }
+            CouncilAction::Upgrade(wasm_hash) => Self::upgrade_internal(&env, wasm_hash),
+            CouncilAction::GrantRole(role, account) => {
+                Self::grant_role_internal(&env, admin, role, account)
+            }
+            CouncilAction::RevokeRole(Role::Distributor, account) if timelocked => {
+                Self::queue_operation_internal(&env, TimelockOp::RemoveDistributor(account));
+            }
+            CouncilAction::RevokeRole(role, account) => {
+                Self::revoke_role_internal(&env, admin, role, account)?
+            }
+            CouncilAction::SetAllowance(
+                distributor,
+                token,
+                amount,
+                refill_amount,
+                refill_period,
+            ) if timelocked => {
+                Self::queue_operation_internal(
+                    &env,
+                    TimelockOp::SetAllowance(
+                        distributor,
+                        token,
+                        amount,
+                        refill_amount,
+                        refill_period,
+                    ),
+                );
+            }
+            CouncilAction::SetAllowance(
+                distributor,
+                token,
+                amount,
+                refill_amount,
+                refill_period,
+            ) => Self::set_allowance_internal(
+                &env,
+                distributor,
+                token,
+                amount,
+                refill_amount,
+                refill_period,
+            )?,
+            CouncilAction::ReassignRecipient(id, new_recipient, reason, false) if timelocked => {
+                Self::queue_operation_internal(
+                    &env,
+                    TimelockOp::ReassignRecipient(id, new_recipient, reason),
+                );
+            }
+            CouncilAction::ReassignRecipient(id, new_recipient, reason, with_consent) => {
+                Self::reassign_internal(&env, admin, id, new_recipient, reason, with_consent)?
+            }
+            CouncilAction::ProposeAdmin(new_admin, expires_at) => {
+                Self::propose_admin_internal(&env, admin, new_admin, expires_at)?
+            }
+            CouncilAction::CancelAdminTransfer => {
+                Self::cancel_admin_transfer_internal(&env, admin)?
+            }
+            CouncilAction::Pause => Self::pause_internal(&env, admin)?,
+            CouncilAction::Unpause => Self::unpause_internal(&env, admin)?,
+            CouncilAction::AddSigner(signer) => {
+                let mut council = council;
+                if council.signers.contains(signer.clone()) {
+                    return Err(Error::InvalidState);
+                }
+                council.signers.push_back(signer);
+                Self::store_council(&env, council)?;
+            }
+            CouncilAction::RemoveSigner(signer) => {
+                let mut council = council;
+                let index = council
+                    .signers
+                    .first_index_of(signer)
+                    .ok_or(Error::InvalidState)?;
+                council.signers.remove(index);
+                Self::store_council(&env, council)?;
+            }
+            CouncilAction::SetThreshold(threshold) => {
+                let mut council = council;
+                council.threshold = threshold;
+                Self::store_council(&env, council)?;
+            }
+        }
+
+        ProposalExecutedEvent { id: proposal_id }.publish(&env);
+
+        Ok(())
+    }
+
+    pub fn get_proposal(env: Env, proposal_id: u64) -> Result<Proposal, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Proposal(proposal_id))
+            .ok_or(Error::ProposalNotFound)
+    }
+
+    // --- Timelock ---
+
+    /// Enables the timelock. Once the delay is non-zero, `withdraw_surplus`,
+    /// `set_config` and `remove_distributor` can only take effect through
+    /// `queue_operation` / `execute_operation`, and further delay changes must
+    /// be queued as well.
+    ///
+    /// Paths that could otherwise move pool funds to an address the admin
+    /// controls are held back too: refunds, reassignments without the
+    /// recipient's consent and allowance increases must be queued, and the
+    /// admin is held to its own allowance when creating packages, voucher
+    /// budgets and distributions.
+    ///
+    /// Goes through the council, as `CouncilAction::SetTimelockDelay`, once
+    /// one is configured.
+    pub fn set_timelock_delay(env: Env, delay: u64) -> Result<(), Error> {
+        Self::require_sole_admin(&env)?;
+        Self::check_timelock(&env)?;
+        Self::set_timelock_delay_internal(&env, delay)
+    }
+
+    pub fn get_timelock_delay(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TimelockDelay)
+            .unwrap_or(0)
+    }
+
+    /// Schedules a sensitive operation to run after the timelock delay.
+    /// Once a council is configured, operations are queued by executing the
+    /// matching council proposal instead. Returns the operation id.
+    pub fn queue_operation(env: Env, op: TimelockOp) -> Result<u64, Error> {
+        Self::require_sole_admin(&env)?;
+
+        if Self::get_timelock_delay(env.clone()) == 0 {
+            return Err(Error::InvalidState);
+        }
+
+        Ok(Self::queue_operation_internal(&env, op))
+    }
+
+    /// Cancels a queued operation during its delay. Goes through the council,
+    /// as `CouncilAction::CancelOperation`, once one is configured.
+    pub fn cancel_operation(env: Env, op_id: u64) -> Result<(), Error> {
+        let admin = Self::require_sole_admin(&env)?;
+        Self::cancel_operation_internal(&env, admin, op_id)
+    }
+
+    /// Executes a queued operation once its eta has been reached. Callable by anyone.
+    pub fn execute_operation(env: Env, op_id: u64) -> Result<(), Error> {
+        let mut operation = Self::get_operation(env.clone(), op_id)?;
+        if operation.status != OperationStatus::Queued {
+            return Err(Error::OperationNotQueued);
+        }
+        if env.ledger().timestamp() < operation.eta {
+            return Err(Error::TimelockNotReady);
+        }
+
+        // Mark executed before running the operation (Re-entrancy protection)
+        operation.status = OperationStatus::Executed;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Operation(op_id), &operation);
+
+        match operation.op {
+            TimelockOp::WithdrawSurplus(to, amount, token) => {
+                Self::withdraw_surplus_internal(&env, to, amount, token)?
+            }
+            TimelockOp::SetConfig(config) => Self::set_config_internal(&env, config)?,
+            TimelockOp::RemoveDistributor(addr) => {
+                let admin = Self::get_admin(env.clone())?;
+                Self::revoke_role_internal(&env, admin, Role::Distributor, addr)?
+            }
+            TimelockOp::SetDelay(delay) => Self::set_timelock_delay_internal(&env, delay)?,
+            TimelockOp::Upgrade(wasm_hash) => Self::upgrade_internal(&env, wasm_hash),
+            TimelockOp::SetAllowance(distributor, token, amount, refill_amount, refill_period) => {
+                Self::set_allowance_internal(
+                    &env,
+                    distributor,
+                    token,
+                    amount,
+                    refill_amount,
+                    refill_period,
+                )?
+            }
+            TimelockOp::ReassignRecipient(id, new_recipient, reason) => {
+                let admin = Self::get_admin(env.clone())?;
+                Self::reassign_internal(&env, admin, id, new_recipient, reason, false)?
+            }
+            TimelockOp::Refund(ids) => {
+                let admin = Self::get_admin(env.clone())?;
+                Self::settle(&env, |settlement| {
+                    let mut total: i128 = 0;
+                    for id in ids.iter() {
+                        total += Self::refund_internal(&env, settlement, admin.clone(), id)?;
+                    }
+                    Ok(total)
+                })?
+            }
+        }
+
+        OperationExecutedEvent { id: op_id }.publish(&env);
+
+        Ok(())
+    }
+
+    pub fn get_operation(env: Env, op_id: u64) -> Result<QueuedOperation, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Operation(op_id))
+            .ok_or(Error::OperationNotFound)
+    }
+
+    // --- Upgrades ---
+
+    /// Replaces the contract code in place, keeping storage. Call `migrate`
+    /// afterwards when the new code uses a newer storage schema. Goes through
+    /// the council and the timelock when those are enabled.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        Self::require_sole_admin(&env)?;
+        Self::check_timelock(&env)?;
+        Self::upgrade_internal(&env, new_wasm_hash);
+        Ok(())
+    }
+
+    /// Storage schema version of this deployment. Deployments that predate
+    /// versioning report 1.
+    pub fn version(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::Version).unwrap_or(1)
+    }
+
+    /// Moves storage written by the first release (schema v1) to the current
+    /// layout, `limit` (capped at `MAX_MIGRATION_BATCH`, 0 meaning the cap)
+    /// packages at a time. `cursor` must be the value returned by the previous
+    /// call (0 for the first). Returns the next cursor, or `None` once every
+    /// package has been rewritten and the new version recorded. Requires the
+    /// admin's auth.
+    ///
+    /// The contract behaves as paused until the migration completes. Counters
+    /// for migrated packages are then filled in by `rebuild_aggregates`.
+    pub fn migrate(env: Env, cursor: u64, limit: u32) -> Result<Option<u64>, Error> {
+        let instance = env.storage().instance();
+        let mut progress = match instance.get::<_, MigrationProgress>(&DataKey::Migration) {
+            Some(progress) => {
+                let admin: Address = instance.get(&DataKey::Admin).ok_or(Error::NotInitialized)?;
+                admin.require_auth();
+                progress
+            }
+            None => {
+                if Self::version(env.clone()) >= SCHEMA_VERSION {
+                    return Err(Error::InvalidState);
+                }
+                let admin: Address = instance.get(&V1_KEY_ADMIN).ok_or(Error::NotInitialized)?;
+                admin.require_auth();
+                Self::migrate_instance(&env)
+            }
+        };
+        // Each position must be rewritten exactly once
+        if cursor != progress.next {
+            return Err(Error::InvalidState);
+        }
+
+        let admin = Self::get_admin(env.clone())?;
+        let end = progress
+            .end
+            .min(cursor.saturating_add(Self::page_size(limit, MAX_MIGRATION_BATCH)));
+        for idx in cursor..end {
+            Self::migrate_package(&env, &admin, idx);
+        }
+        Self::bump_instance_ttl(&env);
+
+        if end < progress.end {
+            progress.next = end;
+            instance.set(&DataKey::Migration, &progress);
+            return Ok(Some(end));
+        }
+
+        instance.remove(&DataKey::Migration);
+        instance.set(&DataKey::Version, &SCHEMA_VERSION);
+        MigratedEvent {
+            from_version: 1,
+            to_version: SCHEMA_VERSION,
+        }
+        .publish(&env);
+
+        Ok(None)
+    }
+
+    // --- Storage Maintenance ---
+
+    /// Extends the TTL of the given packages, their index entries and the
+    /// contract instance. Callable by the admin or an `Auditor` (keeper).
+    /// Unknown ids are skipped.
+    pub fn bump_packages(env: Env, caller: Address, ids: Vec<u64>) -> Result<(), Error> {
+        Self::require_admin_or_role(&env, &caller, Role::Auditor)?;
+        for id in ids.iter() {
+            Self::bump_package(&env, id);
+        }
+        Self::bump_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Extends the TTL of the contract instance (config, roles, counters and
+    /// locked totals). Callable by the admin or an `Auditor` (keeper).
+    pub fn bump_instance(env: Env, caller: Address) -> Result<(), Error> {
+        Self::require_admin_or_role(&env, &caller, Role::Auditor)?;
+        Self::bump_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Returns unclaimed packages whose storage is due to be archived within
+    /// `within_ledgers`, scanning up to `limit` aggregation index positions
+    /// (capped at `MAX_PAGE_SIZE`, 0 meaning the cap) from `cursor`. Packages
+    /// migrated without TTL records are always reported.
+    pub fn get_packages_near_archival(
+        env: Env,
+        cursor: u64,
+        limit: u32,
+        within_ledgers: u32,
+    ) -> PackageTtlPage {
+        let count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PackageIndexCount)
+            .unwrap_or(0);
+        let end = count.min(cursor.saturating_add(Self::page_size(limit, MAX_PAGE_SIZE)));
+        let horizon = env.ledger().sequence().saturating_add(within_ledgers);
+
+        let mut records = Vec::new(&env);
+        for idx in cursor..end {
+            let Some(id) = env
+                .storage()
+                .persistent()
+                .get::<_, u64>(&DataKey::PackageIndex(idx))
+            else {
+                continue;
+            };
+            let Some(package) = env
+                .storage()
+                .persistent()
+                .get::<_, Package>(&DataKey::Package(id))
+            else {
+                continue;
+            };
+            if !package.is_active() {
+                continue;
+            }
+
+            let ttl = env
+                .storage()
+                .persistent()
+                .get(&DataKey::PackageTtl(id))
+                .unwrap_or(PackageTtl {
+                    id,
+                    index: idx,
+                    recipient_index: 0,
+                    live_until: 0,
+                });
+            if ttl.live_until <= horizon {
+                records.push_back(ttl);
+            }
+        }
+
+        PackageTtlPage {
+            records,
+            next_cursor: if end < count { Some(end) } else { None },
+        }
+    }
+
+    // --- Funding & Packages ---
+
+    /// Funds the contract (Pool Model).
+    /// Transfers `amount` of `token` from `from` to this contract.
+    /// This increases the contract's balance, allowing new packages to be created.
+    pub fn fund(env: Env, token: Address, from: Address, amount: i128) -> Result<(), Error> {
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        from.require_auth();
+
+        // Perform transfer: From -> Contract
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&from, env.current_contract_address(), &amount);
+
+        // Emit event
+        FundEvent {
+            from,
+            token,
+            amount,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Creates a package with a specific ID, at most `u64::MAX / 2`.
+    /// Locks funds from the available pool (Contract Balance - Total Locked).
+    pub fn create_package(
+        env: Env,
+        operator: Address,
+        id: u64,
+        recipient: Address,
+        amount: i128,
+        token: Address,
+        expires_at: u64,
+    ) -> Result<u64, Error> {
+        Self::check_paused(&env)?;
+        Self::require_role_or_sole_admin(&env, &operator, Role::Distributor)?;
+
+        let package = Package {
+            recipient: Some(recipient),
+            ..Self::new_standard_package(&env, &operator, id, amount, &token, expires_at)
+        };
+        Self::create_package_internal(&env, package)?;
+        Self::consume_allowance(&env, &operator, &token, amount)?;
+
+        Ok(id)
+    }
+
+    /// Creates a package that is released linearly over `schedule`. The
+    /// recipient can claim the vested amount at any time; revoking it pays
+    /// out what has vested and returns only the rest to the pool.
+    /// `expires_at` must be 0 or no earlier than `schedule.end`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_vesting_package(
+        env: Env,
+        operator: Address,
+        id: u64,
+        recipient: Address,
+        amount: i128,
+        token: Address,
+        schedule: VestingSchedule,
+        expires_at: u64,
+    ) -> Result<u64, Error> {
+        Self::check_paused(&env)?;
+        Self::require_role_or_sole_admin(&env, &operator, Role::Distributor)?;
+
+        let package = Package {
+            id,
+            recipient: Some(recipient),
+            amount,
+            claimed_amount: 0,
+            token: token.clone(),
+            status: PackageStatus::Created,
+            created_at: env.ledger().timestamp(),
+            expires_at,
+            metadata: Map::new(&env),
+            campaign_id: None,
+            created_by: operator.clone(),
+            kind: PackageKind::Vesting(schedule),
+            previous_recipients: Vec::new(&env),
+        };
+        Self::create_package_internal(&env, package)?;
+        Self::consume_allowance(&env, &operator, &token, amount)?;
+
+        Ok(id)
+    }
+
+    /// Creates a stipend paying `amount_per_period` once per period over
+    /// `schedule`. Funds for all periods are locked up front. Revoking the
+    /// package stops the series: released installments are paid out and
+    /// the remaining periods are unlocked. `expires_at` must be 0 or no
+    /// earlier than the start of the last period.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_recurring_package(
+        env: Env,
+        operator: Address,
+        id: u64,
+        recipient: Address,
+        amount_per_period: i128,
+        token: Address,
+        schedule: RecurringSchedule,
+        expires_at: u64,
+    ) -> Result<u64, Error> {
+        Self::check_paused(&env)?;
+        Self::require_role_or_sole_admin(&env, &operator, Role::Distributor)?;
+
+        let amount = amount_per_period
+            .checked_mul(schedule.periods as i128)
+            .ok_or(Error::InvalidAmount)?;
+        let package = Package {
+            id,
+            recipient: Some(recipient),
+            amount,
+            claimed_amount: 0,
+            token: token.clone(),
+            status: PackageStatus::Created,
+            created_at: env.ledger().timestamp(),
+            expires_at,
+            metadata: Map::new(&env),
+            campaign_id: None,
+            created_by: operator.clone(),
+            kind: PackageKind::Recurring(schedule),
+            previous_recipients: Vec::new(&env),
+        };
+        Self::create_package_internal(&env, package)?;
+        Self::consume_allowance(&env, &operator, &token, amount)?;
+
+        Ok(id)
+    }
+
+    /// Creates a package with no recipient that anyone holding the secret
+    /// behind `claim_hash` (its sha256) can claim with `claim_with_secret`.
+    /// Meant for printed or SMS claim codes handed to people without a
+    /// wallet; the secret must carry enough entropy not to be guessed.
+    pub fn create_hashlocked_package(
+        env: Env,
+        operator: Address,
+        id: u64,
+        amount: i128,
+        token: Address,
+        claim_hash: BytesN<32>,
+        expires_at: u64,
+    ) -> Result<u64, Error> {
+        Self::check_paused(&env)?;
+        Self::require_role_or_sole_admin(&env, &operator, Role::Distributor)?;
+
+        let package = Package {
+            id,
+            recipient: None,
+            amount,
+            claimed_amount: 0,
+            token: token.clone(),
+            status: PackageStatus::Created,
+            created_at: env.ledger().timestamp(),
+            expires_at,
+            metadata: Map::new(&env),
+            campaign_id: None,
+            created_by: operator.clone(),
+            kind: PackageKind::HashLocked(claim_hash),
+            previous_recipients: Vec::new(&env),
+        };
+        Self::create_package_internal(&env, package)?;
+        Self::consume_allowance(&env, &operator, &token, amount)?;
+
+        Ok(id)
+    }
+
+    // --- Campaigns ---
+
+    /// Creates a campaign with its own budget. Returns the new campaign id.
+    /// `ends_at` of 0 leaves the campaign open-ended.
+    pub fn create_campaign(
+        env: Env,
+        name: String,
+        token: Address,
+        budget: i128,
+        starts_at: u64,
+        ends_at: u64,
+    ) -> Result<u64, Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        if budget <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if ends_at > 0 && ends_at <= starts_at {
+            return Err(Error::InvalidState);
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::CampaignCounter)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::CampaignCounter, &(id + 1));
+
+        let campaign = Campaign {
+            id,
+            name,
+            token: token.clone(),
+            budget,
+            starts_at,
+            ends_at,
+            status: CampaignStatus::Active,
+            locked: 0,
+            spent: 0,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Campaign(id), &campaign);
+
+        CampaignCreatedEvent { id, token, budget }.publish(&env);
+
+        Ok(id)
+    }
+
+    /// Closes a campaign. No new packages can be created under it; existing
+    /// packages keep their state and can still be claimed or revoked.
+    pub fn close_campaign(env: Env, campaign_id: u64) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id)?;
+        if campaign.status != CampaignStatus::Active {
+            return Err(Error::CampaignNotActive);
+        }
+
+        campaign.status = CampaignStatus::Closed;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Campaign(campaign_id), &campaign);
+
+        CampaignClosedEvent {
+            id: campaign_id,
+            admin,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    pub fn get_campaign(env: Env, campaign_id: u64) -> Result<Campaign, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Campaign(campaign_id))
+            .ok_or(Error::CampaignNotFound)
+    }
+
+    /// Creates a package under a campaign, in the campaign's token.
+    /// Fails with `CampaignBudgetExceeded` if the campaign's locked plus spent
+    /// amount would exceed its budget.
+    pub fn create_campaign_package(
+        env: Env,
+        operator: Address,
+        campaign_id: u64,
+        id: u64,
+        recipient: Address,
+        amount: i128,
+        expires_at: u64,
+    ) -> Result<u64, Error> {
+        Self::check_paused(&env)?;
+        Self::require_role_or_sole_admin(&env, &operator, Role::Distributor)?;
+
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id)?;
+        let now = env.ledger().timestamp();
+        if campaign.status != CampaignStatus::Active
+            || now < campaign.starts_at
+            || (campaign.ends_at > 0 && now > campaign.ends_at)
+        {
+            return Err(Error::CampaignNotActive);
+        }
+        if amount > 0 && campaign.locked + campaign.spent + amount > campaign.budget {
+            return Err(Error::CampaignBudgetExceeded);
+        }
+
+        let package = Package {
+            id,
+            recipient: Some(recipient),
+            amount,
+            claimed_amount: 0,
+            token: campaign.token.clone(),
+            status: PackageStatus::Created,
+            created_at: now,
+            expires_at,
+            metadata: Map::new(&env),
+            campaign_id: Some(campaign_id),
+            created_by: operator.clone(),
+            kind: PackageKind::Standard,
+            previous_recipients: Vec::new(&env),
+        };
+        Self::create_package_internal(&env, package)?;
+        Self::consume_allowance(&env, &operator, &campaign.token, amount)?;
+
+        campaign.locked += amount;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Campaign(campaign_id), &campaign);
+
+        Ok(id)
+    }
+
+    /// Creates multiple packages in a single transaction for multiple recipients.
+    /// Uses an auto-incrementing counter for package IDs. Fails as a whole if
+    /// any row is invalid; see `batch_create_best_effort`.
+    pub fn batch_create_packages(
+        env: Env,
+        operator: Address,
+        recipients: Vec<Address>,
+        amounts: Vec<i128>,
+        token: Address,
+        expires_in: u64,
+    ) -> Result<Vec<u64>, Error> {
+        let (created_ids, _) = Self::batch_create_internal(
+            &env, operator, recipients, amounts, token, expires_in, false,
+        )?;
+        Ok(created_ids)
+    }
+
+    /// Like `batch_create_packages`, but skips rows with an invalid amount or
+    /// that the pool or the operator's allowance cannot cover, and creates the
+    /// rest. Returns one result per row, in order.
+    pub fn batch_create_best_effort(
+        env: Env,
+        operator: Address,
+        recipients: Vec<Address>,
+        amounts: Vec<i128>,
+        token: Address,
+        expires_in: u64,
+    ) -> Result<Vec<BatchCreateResult>, Error> {
+        let (_, results) = Self::batch_create_internal(
+            &env, operator, recipients, amounts, token, expires_in, true,
+        )?;
+        Ok(results)
+    }
+
+    /// Dry run of `create_package`: runs the same checks without requiring
+    /// auth or changing state, and reports every one that fails.
+    pub fn validate_package(
+        env: Env,
+        operator: Address,
+        id: u64,
+        amount: i128,
+        token: Address,
+        expires_at: u64,
+    ) -> Result<PackageDiagnostics, Error> {
+        let config = Self::get_config(env.clone());
+        let package = Self::new_standard_package(&env, &operator, id, amount, &token, expires_at);
+        let available = Self::available_balance(&env, &token);
+        let allowance = Self::remaining_allowance(&env, &operator, &token)?;
+
+        let errors = Self::error_codes(
+            &env,
+            Self::operator_checks(&env, &operator)?
+                .into_iter()
+                .chain(Self::package_checks(&env, &config, &package))
+                .chain(Self::funding_checks(amount, available, allowance)),
+        );
+        Ok(PackageDiagnostics { id, errors })
+    }
+
+    /// Dry run of `batch_create_packages`: runs the same checks on every row
+    /// without requiring auth or changing state. IDs, locked totals and the
+    /// allowance advance only past rows without errors, as in
+    /// `batch_create_best_effort`; `batch_create_packages` succeeds only if
+    /// no row and no batch-level check fails.
+    pub fn validate_batch(
+        env: Env,
+        operator: Address,
+        recipients: Vec<Address>,
+        amounts: Vec<i128>,
+        token: Address,
+        expires_in: u64,
+    ) -> Result<BatchDiagnostics, Error> {
+        let mut batch_checks = Vec::new(&env);
+        batch_checks.extend(Self::operator_checks(&env, &operator)?);
+        if recipients.len() != amounts.len() {
+            batch_checks.push_back(Err(Error::MismatchedArrays));
+        }
+        let errors = Self::error_codes(&env, batch_checks);
+
+        let mut rows = Vec::new(&env);
+        if recipients.len() != amounts.len() {
+            return Ok(BatchDiagnostics { errors, rows });
+        }
+
+        let config = Self::get_config(env.clone());
+        let expires_at = env.ledger().timestamp() + expires_in;
+        let mut available = Self::available_balance(&env, &token);
+        let mut allowance = Self::remaining_allowance(&env, &operator, &token)?;
+        let mut counter: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PackageCounter)
+            .unwrap_or(0);
+
+        for amount in amounts.iter() {
+            let id = Self::next_free_id(&env, counter)?;
+            counter = id;
+            let package =
+                Self::new_standard_package(&env, &operator, id, amount, &token, expires_at);
+            let row_errors = Self::error_codes(
+                &env,
+                Self::package_checks(&env, &config, &package)
+                    .into_iter()
+                    .chain(Self::funding_checks(amount, available, allowance)),
+            );
+            let created = row_errors.is_empty();
+            rows.push_back(PackageDiagnostics {
+                id,
+                errors: row_errors,
+            });
+            if created {
+                counter = id.checked_add(1).ok_or(Error::PackageIdOutOfRange)?;
+                available -= amount;
+                allowance -= amount;
+            }
+        }
+
+        Ok(BatchDiagnostics { errors, rows })
+    }
+
+    // --- Recipient Actions ---
+
+    /// Recipient claims the package, or whatever remains of it after
+    /// partial claims.
+    pub fn claim(env: Env, id: u64) -> Result<(), Error> {
+        Self::settle(&env, |settlement| {
+            Self::claim_internal(&env, settlement, id, None, None)
+        })
+    }
+
+    /// Recipient claims `amount` of the package, leaving the rest claimable.
+    /// The package becomes `PartiallyClaimed` until nothing remains.
+    pub fn claim_partial(env: Env, id: u64, amount: i128) -> Result<(), Error> {
+        Self::settle(&env, |settlement| {
+            Self::claim_internal(&env, settlement, id, Some(amount), None)
+        })
+    }
+
+    /// Recipient claims the package, like `claim`, but has the funds sent to
+    /// `destination`, e.g. a new wallet or a mobile-money anchor account.
+    pub fn claim_to(env: Env, id: u64, destination: Address) -> Result<(), Error> {
+        Self::settle(&env, |settlement| {
+            Self::claim_internal(&env, settlement, id, None, Some(destination))
+        })
+    }
+
+    /// Commits to claiming a hash-locked package to a destination without
+    /// revealing the secret. `commitment` is the sha256 of `(secret, to)` as
+    /// XDR. Anyone may commit; only a commitment matching the secret is ever
+    /// used.
+    pub fn commit_secret_claim(env: Env, id: u64, commitment: BytesN<32>) -> Result<(), Error> {
+        let package = Self::load_claimable(&env, id)?;
+        if !matches!(package.kind, PackageKind::HashLocked(_)) {
+            return Err(Error::InvalidState);
+        }
+
+        // Re-committing keeps the original ledger
+        let key = DataKey::ClaimCommitment(id, commitment.clone());
+        if !env.storage().persistent().has(&key) {
+            env.storage()
+                .persistent()
+                .set(&key, &env.ledger().sequence());
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, PACKAGE_TTL_EXTEND, PACKAGE_TTL_EXTEND);
+        }
+
+        ClaimCommittedEvent { id, commitment }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Claims a hash-locked package in full by revealing its secret. `to`
+    /// becomes the package's recipient and receives the funds; it does not
+    /// need to sign. `to` must have been committed to with
+    /// `commit_secret_claim` in an earlier ledger, so a secret seen in a
+    /// pending claim cannot be used to redirect the funds.
+    pub fn claim_with_secret(env: Env, id: u64, secret: Bytes, to: Address) -> Result<(), Error> {
+        let mut package = Self::load_claimable(&env, id)?;
+        let PackageKind::HashLocked(claim_hash) = package.kind.clone() else {
+            return Err(Error::InvalidState);
+        };
+        if env.crypto().sha256(&secret).to_bytes() != claim_hash {
+            return Err(Error::InvalidSecret);
+        }
+
+        let commitment = env
+            .crypto()
+            .sha256(&(secret, to.clone()).to_xdr(&env))
+            .to_bytes();
+        let key = DataKey::ClaimCommitment(id, commitment);
+        let committed_at: u32 = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::ClaimNotCommitted)?;
+        if committed_at >= env.ledger().sequence() {
+            return Err(Error::ClaimNotCommitted);
+        }
+        env.storage().persistent().remove(&key);
+
+        Self::assign_recipient(&env, &mut package, &to);
+        let payout = package.remaining();
+        let mut settlement = Settlement::new(&env);
+        Self::apply_claim(&env, &mut settlement, &mut package, payout, &to);
+        settlement.apply(&env);
+        Ok(())
+    }
+
+    /// Recipient authorizes `delegate` to claim one of their packages, or all
+    /// of them when `package_id` is `None`, via `claim_on_behalf`. Funds go
+    /// to the recipient unless `pay_delegate` is set. Replaces any previous
+    /// delegate for the same scope; a per-package delegate takes precedence.
+    pub fn set_claim_delegate(
+        env: Env,
+        recipient: Address,
+        package_id: Option<u64>,
+        delegate: Address,
+        pay_delegate: bool,
+    ) -> Result<(), Error> {
+        recipient.require_auth();
+        let key = Self::delegate_key(&env, &recipient, package_id)?;
+
+        let delegation = ClaimDelegation {
+            recipient: recipient.clone(),
+            delegate: delegate.clone(),
+            pay_delegate,
+        };
+        env.storage().persistent().set(&key, &delegation);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PACKAGE_TTL_EXTEND, PACKAGE_TTL_EXTEND);
+
+        DelegateSetEvent {
+            recipient,
+            package_id,
+            delegate,
+            pay_delegate,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Recipient withdraws a delegation set with `set_claim_delegate`.
+    pub fn revoke_claim_delegate(
+        env: Env,
+        recipient: Address,
+        package_id: Option<u64>,
+    ) -> Result<(), Error> {
+        recipient.require_auth();
+        let key = Self::delegate_key(&env, &recipient, package_id)?;
+        if !env.storage().persistent().has(&key) {
+            return Err(Error::InvalidState);
+        }
+        env.storage().persistent().remove(&key);
+
+        DelegateRevokedEvent {
+            recipient,
+            package_id,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    pub fn get_claim_delegate(
+        env: Env,
+        recipient: Address,
+        package_id: Option<u64>,
+    ) -> Option<ClaimDelegation> {
+        let key = Self::delegate_key(&env, &recipient, package_id).ok()?;
+        env.storage().persistent().get(&key)
+    }
+
+    /// The recipient's delegate claims everything currently available on a
+    /// package. Requires the delegate's auth only.
+    pub fn claim_on_behalf(env: Env, id: u64) -> Result<(), Error> {
+        let mut package = Self::load_claimable(&env, id)?;
+        let recipient = package.recipient.clone().ok_or(Error::InvalidState)?;
+
+        // A delegation only counts while it was granted by the current recipient
+        let delegation = [
+            DataKey::PackageDelegate(id),
+            DataKey::RecipientDelegate(recipient.clone()),
+        ]
+        .into_iter()
+        .filter_map(|key| env.storage().persistent().get::<_, ClaimDelegation>(&key))
+        .find(|delegation| delegation.recipient == recipient)
+        .ok_or(Error::NotAuthorized)?;
+
+        let payout = package.available(env.ledger().timestamp());
+        if payout == 0 {
+            return Err(Error::NothingToClaim);
+        }
+
+        // Auth
+        delegation.delegate.require_auth();
+
+        let to = if delegation.pay_delegate {
+            &delegation.delegate
+        } else {
+            &recipient
+        };
+        let mut settlement = Settlement::new(&env);
+        Self::apply_claim(&env, &mut settlement, &mut package, payout, to);
+        settlement.apply(&env);
+
+        ClaimedOnBehalfEvent {
+            id,
+            recipient,
+            delegate: delegation.delegate,
+            amount: payout,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Amount the recipient could claim right now: the unclaimed remainder,
+    /// or for vesting packages the vested part not yet claimed. Zero for
+    /// inactive or expired packages.
+    pub fn claimable_now(env: Env, id: u64) -> Result<i128, Error> {
+        let package = Self::get_package(env.clone(), id)?;
+        let now = env.ledger().timestamp();
+        if !package.is_active() || (package.expires_at > 0 && now > package.expires_at) {
+            return Ok(0);
+        }
+        Ok(package.available(now))
+    }
+
+    // --- Vouchers ---
+
+    /// Registers the ed25519 key that signs `operator`'s vouchers, replacing
+    /// any previous one. Vouchers signed by a replaced key can no longer be
+    /// redeemed. Each operator's vouchers are paid from its own budget only.
+    pub fn set_voucher_signer(
+        env: Env,
+        operator: Address,
+        public_key: BytesN<32>,
+    ) -> Result<(), Error> {
+        Self::require_role_or_sole_admin(&env, &operator, Role::Distributor)?;
+        let key = DataKey::VoucherSigner(operator.clone());
+        env.storage().persistent().set(&key, &public_key);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PACKAGE_TTL_EXTEND, PACKAGE_TTL_EXTEND);
+
+        VoucherSignerSetEvent {
+            public_key,
+            operator,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    pub fn get_voucher_signer(env: Env, issuer: Address) -> Option<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::VoucherSigner(issuer))
+    }
+
+    /// Locks `amount` of the pool as `operator`'s budget for redeeming the
+    /// vouchers it issues in `token`. Vouchers are paid from their issuer's
+    /// budget only, so no more than it has set aside can ever be redeemed.
+    /// Subject to `Config.min_amount` and `Config.allowed_tokens`, and counts
+    /// against a distributor's allowance, like package creation.
+    pub fn fund_voucher_budget(
+        env: Env,
+        operator: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<i128, Error> {
+        Self::check_paused(&env)?;
+        Self::require_role_or_sole_admin(&env, &operator, Role::Distributor)?;
+        let config = Self::get_config(env.clone());
+        Self::config_checks(&env, &config, amount, &token, None)
+            .into_iter()
+            .collect::<Result<(), Error>>()?;
+
+        Self::lock_funds(&env, &token, amount)?;
+        Self::consume_allowance(&env, &operator, &token, amount)?;
+        let budget =
+            Self::get_voucher_budget(env.clone(), operator.clone(), token.clone()) + amount;
+        Self::store_voucher_budget(&env, &operator, &token, budget);
+        Ok(budget)
+    }
+
+    /// Admin returns `amount` of `issuer`'s unused voucher budget to the pool,
+    /// giving it back to the issuer's allowance.
+    pub fn release_voucher_budget(
+        env: Env,
+        issuer: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<i128, Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let budget = Self::get_voucher_budget(env.clone(), issuer.clone(), token.clone());
+        if amount <= 0 || amount > budget {
+            return Err(Error::InvalidAmount);
+        }
+
+        Self::decrement_locked(&env, &token, amount);
+        Self::restore_allowance(&env, &issuer, &token, amount);
+        Self::store_voucher_budget(&env, &issuer, &token, budget - amount);
+        Ok(budget - amount)
+    }
+
+    pub fn get_voucher_budget(env: Env, issuer: Address, token: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::VoucherBudget(issuer, token))
+            .unwrap_or(0)
+    }
+
+    pub fn is_voucher_spent(env: Env, issuer: Address, id: u64) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::VoucherSpent(issuer, id))
+    }
+
+    /// Pays out a voucher signed by its issuer's registered signer key from
+    /// the issuer's budget. Anyone may submit it; funds always go to
+    /// `voucher.recipient`. Each issuer's voucher id can be redeemed once, and only
+    /// while the issuer is the admin or still a `Distributor`. An invalid
+    /// signature aborts the call.
+    pub fn redeem_voucher(env: Env, voucher: Voucher, signature: BytesN<64>) -> Result<(), Error> {
+        Self::check_paused(&env)?;
+        let public_key = Self::get_voucher_signer(env.clone(), voucher.issuer.clone())
+            .ok_or(Error::InvalidState)?;
+        if voucher.issuer != Self::get_admin(env.clone())?
+            && !Self::has_role(env.clone(), Role::Distributor, voucher.issuer.clone())
+        {
+            return Err(Error::NotAuthorized);
+        }
+
+        if voucher.amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if voucher.expires_at > 0 && env.ledger().timestamp() > voucher.expires_at {
+            return Err(Error::PackageExpired);
+        }
+        let spent_key = DataKey::VoucherSpent(voucher.issuer.clone(), voucher.id);
+        if env.storage().persistent().has(&spent_key) {
+            return Err(Error::VoucherSpent);
+        }
+
+        let message = (env.current_contract_address(), voucher.clone()).to_xdr(&env);
+        env.crypto()
+            .ed25519_verify(&public_key, &message, &signature);
+
+        let budget =
+            Self::get_voucher_budget(env.clone(), voucher.issuer.clone(), voucher.token.clone());
+        if budget < voucher.amount {
+            return Err(Error::InsufficientFunds);
+        }
+
+        // Record the redemption before paying out
+        env.storage().persistent().set(&spent_key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&spent_key, PACKAGE_TTL_EXTEND, PACKAGE_TTL_EXTEND);
+        Self::store_voucher_budget(
+            &env,
+            &voucher.issuer,
+            &voucher.token,
+            budget - voucher.amount,
+        );
+        Self::decrement_locked(&env, &voucher.token, voucher.amount);
+
+        let token_client = token::Client::new(&env, &voucher.token);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &voucher.recipient,
+            &voucher.amount,
+        );
+
+        VoucherRedeemedEvent {
+            id: voucher.id,
+            issuer: voucher.issuer,
+            recipient: voucher.recipient,
+            token: voucher.token,
+            amount: voucher.amount,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // --- Merkle Distributions ---
+
+    /// Creates a distribution of up to `total` in `token` to the leaves of
+    /// `merkle_root`, locking `total` from the pool. Returns the new
+    /// distribution id. Whatever is unclaimed at `expires_at` can be
+    /// returned to the pool with `close_distribution`. `Config` applies to
+    /// `total`, `token` and `expires_at` as it does to a package.
+    pub fn create_distribution(
+        env: Env,
+        operator: Address,
+        token: Address,
+        merkle_root: BytesN<32>,
+        total: i128,
+        expires_at: u64,
+    ) -> Result<u64, Error> {
+        Self::check_paused(&env)?;
+        Self::require_role_or_sole_admin(&env, &operator, Role::Distributor)?;
+        let config = Self::get_config(env.clone());
+        Self::config_checks(&env, &config, total, &token, Some(expires_at))
+            .into_iter()
+            .collect::<Result<(), Error>>()?;
+        if expires_at <= env.ledger().timestamp() {
+            return Err(Error::InvalidState);
+        }
+
+        Self::lock_funds(&env, &token, total)?;
+        Self::consume_allowance(&env, &operator, &token, total)?;
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::DistributionCounter)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::DistributionCounter, &(id + 1));
+
+        let distribution = Distribution {
+            id,
+            token: token.clone(),
+            merkle_root: merkle_root.clone(),
+            total,
+            claimed: 0,
+            expires_at,
+            created_by: operator,
+            closed: false,
+        };
+        Self::save_distribution(&env, &distribution);
+
+        DistributionCreatedEvent {
+            id,
+            token,
+            merkle_root,
+            total,
+        }
+        .publish(&env);
+
+        Ok(id)
+    }
+
+    pub fn get_distribution(env: Env, id: u64) -> Result<Distribution, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Distribution(id))
+            .ok_or(Error::DistributionNotFound)
+    }
+
+    pub fn is_merkle_claimed(env: Env, id: u64, index: u32) -> bool {
+        let word: u128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::DistributionClaims(id, index / 128))
+            .unwrap_or(0);
+        word & (1 << (index % 128)) != 0
+    }
+
+    /// Pays `amount` to `recipient` for leaf `index` of a distribution, given
+    /// the sibling hashes from the leaf up to the root. Anyone may submit a
+    /// claim; funds always go to the recipient in the leaf.
+    pub fn claim_merkle(
+        env: Env,
+        id: u64,
+        index: u32,
+        recipient: Address,
+        amount: i128,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<(), Error> {
+        Self::check_paused(&env)?;
+        let mut distribution = Self::get_distribution(env.clone(), id)?;
+        if distribution.closed {
+            return Err(Error::InvalidState);
+        }
+        if env.ledger().timestamp() > distribution.expires_at {
+            return Err(Error::PackageExpired);
+        }
+        if Self::is_merkle_claimed(env.clone(), id, index) {
+            return Err(Error::AlreadyClaimed);
+        }
+
+        let leaf = (index, recipient.clone(), amount).to_xdr(&env);
+        let mut node = env.crypto().sha256(&leaf).to_bytes();
+        for sibling in proof.iter() {
+            let mut pair = Bytes::new(&env);
+            if node < sibling {
+                pair.append(&node.into());
+                pair.append(&sibling.into());
+            } else {
+                pair.append(&sibling.into());
+                pair.append(&node.into());
+            }
+            node = env.crypto().sha256(&pair).to_bytes();
+        }
+        if node != distribution.merkle_root {
+            return Err(Error::InvalidProof);
+        }
+
+        // A root committing to more than was locked cannot overdraw the pool
+        if amount <= 0 || distribution.claimed + amount > distribution.total {
+            return Err(Error::InsufficientFunds);
+        }
+
+        // Record the claim before paying out
+        let word_key = DataKey::DistributionClaims(id, index / 128);
+        let word: u128 = env.storage().persistent().get(&word_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&word_key, &(word | (1 << (index % 128))));
+        env.storage()
+            .persistent()
+            .extend_ttl(&word_key, PACKAGE_TTL_EXTEND, PACKAGE_TTL_EXTEND);
+        distribution.claimed += amount;
+        Self::save_distribution(&env, &distribution);
+        Self::decrement_locked(&env, &distribution.token, amount);
+
+        let token_client = token::Client::new(&env, &distribution.token);
+        token_client.transfer(&env.current_contract_address(), &recipient, &amount);
+
+        MerkleClaimedEvent {
+            id,
+            index,
+            recipient,
+            amount,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Admin returns the unclaimed remainder of an expired distribution to
+    /// the pool. Returns the amount unlocked.
+    pub fn close_distribution(env: Env, id: u64) -> Result<i128, Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let mut distribution = Self::get_distribution(env.clone(), id)?;
+        if distribution.closed || env.ledger().timestamp() <= distribution.expires_at {
+            return Err(Error::InvalidState);
+        }
+
+        let unclaimed = distribution.total - distribution.claimed;
+        distribution.closed = true;
+        Self::save_distribution(&env, &distribution);
+        Self::decrement_locked(&env, &distribution.token, unclaimed);
+
+        DistributionClosedEvent { id, unclaimed }.publish(&env);
+
+        Ok(unclaimed)
+    }
+
+    // --- Admin Actions ---
+
+    /// Admin corrects the recipient of a package that has not been claimed
+    /// from yet. With `with_consent` the current recipient must authorize
+    /// the change as well. The replaced address is kept in
+    /// `previous_recipients`. Goes through the council once one is
+    /// configured. Without consent, must be queued as
+    /// `TimelockOp::ReassignRecipient` while the timelock is enabled.
+    pub fn reassign_recipient(
+        env: Env,
+        id: u64,
+        new_recipient: Address,
+        reason: String,
+        with_consent: bool,
+    ) -> Result<(), Error> {
+        let admin = Self::require_sole_admin(&env)?;
+        if !with_consent {
+            Self::check_timelock(&env)?;
+        }
+        Self::reassign_internal(&env, admin, id, new_recipient, reason, with_consent)
+    }
+
+    /// Sets a metadata entry on a package. Callable by the admin or the
+    /// distributor that created the package while it still holds the role,
+    /// in any status. At most `MAX_METADATA_KEYS` keys with values of up to
+    /// `MAX_METADATA_VALUE_LEN` bytes are kept. `METADATA_DOC_HASH` only
+    /// accepts a hex SHA-256 digest and, once set, cannot be changed or
+    /// removed.
+    pub fn set_package_metadata(
+        env: Env,
+        caller: Address,
+        id: u64,
+        key: Symbol,
+        value: String,
+    ) -> Result<(), Error> {
+        let mut package = Self::load_for_metadata(&env, &caller, id)?;
+
+        if value.len() > MAX_METADATA_VALUE_LEN {
+            return Err(Error::MetadataLimitExceeded);
+        }
+        if !package.metadata.contains_key(key.clone())
+            && package.metadata.len() >= MAX_METADATA_KEYS
+        {
+            return Err(Error::MetadataLimitExceeded);
+        }
+        if key == METADATA_DOC_HASH {
+            if !Self::is_hex_digest(&value) {
+                return Err(Error::InvalidMetadata);
+            }
+            // The document hash is evidence; it is written once
+            if package.metadata.contains_key(key.clone()) {
+                return Err(Error::InvalidState);
+            }
+        }
+
+        package.metadata.set(key.clone(), value.clone());
+        Self::save_package(&env, &package);
+
+        MetadataUpdatedEvent {
+            id,
+            key,
+            value: Some(value),
+            updated_by: caller,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Removes a metadata entry from a package. Same access as
+    /// `set_package_metadata`; `METADATA_DOC_HASH` cannot be removed.
+    pub fn remove_package_metadata(
+        env: Env,
+        caller: Address,
+        id: u64,
+        key: Symbol,
+    ) -> Result<(), Error> {
+        let mut package = Self::load_for_metadata(&env, &caller, id)?;
+        if key == METADATA_DOC_HASH || package.metadata.remove(key.clone()).is_none() {
+            return Err(Error::InvalidState);
+        }
+        Self::save_package(&env, &package);
+
+        MetadataUpdatedEvent {
+            id,
+            key,
+            value: None,
+            updated_by: caller,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Admin manually triggers disbursement (overrides recipient claim need, strictly checks status).
+    pub fn disburse(env: Env, id: u64) -> Result<(), Error> {
+        let admin = Self::require_sole_admin(&env)?;
+        Self::settle(&env, |settlement| {
+            Self::disburse_internal(&env, settlement, admin, id)
+        })
+    }
+
+    /// Admin revokes a package (Cancels it). Funds are effectively unlocked but remain in contract pool.
+    /// Goes through the council, as `CouncilAction::Revoke`, once one is configured.
+    pub fn revoke(env: Env, id: u64) -> Result<(), Error> {
+        let admin = Self::require_sole_admin(&env)?;
+        Self::settle(&env, |settlement| {
+            Self::revoke_internal(&env, settlement, &admin, id)
+        })
+    }
+
+    /// Refunds an expired or cancelled package to the admin.
+    /// Callable by the admin or a `Refunder`. Must be queued as
+    /// `TimelockOp::Refund` while the timelock is enabled.
+    pub fn refund(env: Env, caller: Address, id: u64) -> Result<(), Error> {
+        Self::require_role_or_sole_admin(&env, &caller, Role::Refunder)?;
+        Self::check_timelock(&env)?;
+        let admin = Self::get_admin(env.clone())?;
+        Self::settle(&env, |settlement| {
+            Self::refund_internal(&env, settlement, admin, id)
+        })
+    }
+
+    /// Admin-only package cancellation.
+    /// Requirements: Admin auth, existing package, status must be 'Created'.
+    /// Goes through the council, as `CouncilAction::Cancel`, once one is configured.
+    pub fn cancel_package(env: Env, package_id: u64) -> Result<(), Error> {
+        // 1. Only the admin can cancel (check stored admin and require_auth)
+        let admin = Self::require_sole_admin(&env)?;
+        Self::settle(&env, |settlement| {
+            Self::cancel_internal(&env, settlement, &admin, package_id)
+        })
+    }
+
+    /// Admin-only package expiration extension.
+    /// Requirements: Admin auth, existing package, status must be 'Created', additional_time > 0.
+    /// Behavior: Adds additional_time to the package's expires_at timestamp.
+    /// Cannot extend unbounded packages (expires_at == 0).
+    pub fn extend_expiration(env: Env, package_id: u64, additional_time: u64) -> Result<(), Error> {
+        // 1. Only the admin can extend (check stored admin and require_auth)
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+        let config = Self::get_config(env.clone());
+
+        // 2. Package must exist
+        let key = DataKey::Package(package_id);
+        let mut package: Package = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::PackageNotFound)?;
+
+        // 3. Package must still be claimable
+        if !package.is_active() {
+            return Err(Error::PackageNotActive);
+        }
+
+        // 4. additional_time must be greater than 0
+        if additional_time == 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        // 5. Package must not be unbounded (expires_at must be > 0)
+        if package.expires_at == 0 {
+            return Err(Error::InvalidState);
+        }
+
+        // 6. Package must not already be expired
+        if env.ledger().timestamp() > package.expires_at {
+            return Err(Error::PackageExpired);
+        }
+
+        // 7. Calculate new expiration and update
+        let old_expires_at = package.expires_at;
+        let new_expires_at = old_expires_at + additional_time;
+        if config.max_expires_in > 0 {
+            let now = env.ledger().timestamp();
+            if new_expires_at <= now || new_expires_at - now > config.max_expires_in {
+                return Err(Error::InvalidState);
+            }
+        }
+        package.expires_at = new_expires_at;
+        Self::save_package(&env, &package);
+
+        // 8. Emit Extended event
+        ExtendedEvent {
+            id: package_id,
+            admin: admin.clone(),
+            old_expires_at,
+            new_expires_at,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Admin-only function to withdraw surplus (unallocated) funds from the contract.
+    /// Requirements: Admin auth, valid amount, sufficient surplus available.
+    /// Behavior: Transfers amount of token from contract to the specified address.
+    pub fn withdraw_surplus(
+        env: Env,
+        to: Address,
+        amount: i128,
+        token: Address,
+    ) -> Result<(), Error> {
+        Self::require_sole_admin(&env)?;
+        Self::check_timelock(&env)?;
+        Self::withdraw_surplus_internal(&env, to, amount, token)
+    }
+
+    // --- Batch Operations ---
+
+    /// Claims each package in full for its recipient, as `claim` does. Each
+    /// recipient must authorize. Failed items are reported and left
+    /// unchanged; the rest are paid with one transfer per recipient and token.
+    pub fn batch_claim(env: Env, ids: Vec<u64>) -> Vec<BatchItemResult> {
+        Self::run_batch(&env, symbol_short!("claim"), ids, |settlement, id| {
+            Self::claim_internal(&env, settlement, id, None, None)
+        })
+    }
+
+    /// Batch form of `disburse`. Goes through the council, as
+    /// `CouncilAction::BatchDisburse`, once one is configured.
+    pub fn batch_disburse(env: Env, ids: Vec<u64>) -> Result<Vec<BatchItemResult>, Error> {
+        let admin = Self::require_sole_admin(&env)?;
+        Ok(Self::run_batch(
+            &env,
+            symbol_short!("disburse"),
+            ids,
+            |settlement, id| Self::disburse_internal(&env, settlement, admin.clone(), id),
+        ))
+    }
+
+    /// Batch form of `revoke`. Goes through the council, as
+    /// `CouncilAction::BatchRevoke`, once one is configured.
+    pub fn batch_revoke(env: Env, ids: Vec<u64>) -> Result<Vec<BatchItemResult>, Error> {
+        let admin = Self::require_sole_admin(&env)?;
+        Ok(Self::run_batch(
+            &env,
+            symbol_short!("revoke"),
+            ids,
+            |settlement, id| Self::revoke_internal(&env, settlement, &admin, id),
+        ))
+    }
+
+    /// Batch form of `cancel_package`. Goes through the council, as
+    /// `CouncilAction::BatchCancel`, once one is configured.
+    pub fn batch_cancel_packages(env: Env, ids: Vec<u64>) -> Result<Vec<BatchItemResult>, Error> {
+        let admin = Self::require_sole_admin(&env)?;
+        Ok(Self::run_batch(
+            &env,
+            symbol_short!("cancel"),
+            ids,
+            |settlement, id| Self::cancel_internal(&env, settlement, &admin, id),
+        ))
+    }
+
+    /// Batch form of `refund`; refunds are paid to the admin in one transfer
+    /// per token. Once a council is configured, the admin goes through
+    /// `CouncilAction::BatchRefund`.
+    pub fn batch_refund(
+        env: Env,
+        caller: Address,
+        ids: Vec<u64>,
+    ) -> Result<Vec<BatchItemResult>, Error> {
+        Self::require_role_or_sole_admin(&env, &caller, Role::Refunder)?;
+        Self::check_timelock(&env)?;
+        let admin = Self::get_admin(env.clone())?;
+        Ok(Self::run_batch(
+            &env,
+            symbol_short!("refund"),
+            ids,
+            |settlement, id| Self::refund_internal(&env, settlement, admin.clone(), id),
+        ))
+    }
+
+    // --- Helpers ---
+
+    /// First ID from `id` on that no package uses yet. Packages created with
+    /// an explicit ID before the counter tracked them can still sit ahead of
+    /// it.
+    fn next_free_id(env: &Env, mut id: u64) -> Result<u64, Error> {
+        while env.storage().persistent().has(&DataKey::Package(id)) {
+            id = id.checked_add(1).ok_or(Error::PackageIdOutOfRange)?;
+        }
+        Ok(id)
+    }
+
+    /// Number of positions a call scans, at most `max`. A zero limit would
+    /// never advance the cursor, so it is treated as `max`.
+    fn page_size(limit: u32, max: u32) -> u64 {
+        if limit == 0 {
+            max as u64
+        } else {
+            limit.min(max) as u64
+        }
+    }
+
+    /// Also rejects calls while a `migrate` is in progress.
+    fn check_paused(env: &Env) -> Result<(), Error> {
+        if env
+            .storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+            || env.storage().instance().has(&DataKey::Migration)
+        {
+            return Err(Error::ContractPaused);
+        }
+        Ok(())
+    }
+
+    /// Creates a package per row. With `best_effort`, invalid rows are
+    /// reported and skipped instead of failing the batch. Returns the created
+    /// ids and the per-row results.
+    fn batch_create_internal(
+        env: &Env,
+        operator: Address,
+        recipients: Vec<Address>,
+        amounts: Vec<i128>,
+        token: Address,
+        expires_in: u64,
+        best_effort: bool,
+    ) -> Result<(Vec<u64>, Vec<BatchCreateResult>), Error> {
+        Self::check_paused(env)?;
+        Self::require_role_or_sole_admin(env, &operator, Role::Distributor)?;
+
+        // Validate array lengths match
+        if recipients.len() != amounts.len() {
+            return Err(Error::MismatchedArrays);
+        }
+
+        let token_client = token::Client::new(env, &token);
+        let contract_balance = token_client.balance(&env.current_contract_address());
+
+        let mut locked_map: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalLocked)
+            .unwrap_or(Map::new(env));
+        let mut current_locked = locked_map.get(token.clone()).unwrap_or(0);
+
+        // Read the current package counter
+        let mut counter: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PackageCounter)
+            .unwrap_or(0);
+        // Read the current aggregation index
+        let mut idx: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PackageIndexCount)
+            .unwrap_or(0);
+
+        let created_at = env.ledger().timestamp();
+        let expires_at = created_at + expires_in;
+
+        let config = Self::get_config(env.clone());
+        let allowance = Self::remaining_allowance(env, &operator, &token)?;
+        let mut created_ids: Vec<u64> = Vec::new(env);
+        let mut results: Vec<BatchCreateResult> = Vec::new(env);
+        let mut total_amount: i128 = 0;
+
+        for i in 0..recipients.len() {
+            let recipient = recipients.get(i).unwrap();
+            let amount = amounts.get(i).unwrap();
+
+            // Create package under the next free ID from the counter
+            let id = Self::next_free_id(env, counter)?;
+            counter = id;
+            let package = Package {
+                recipient: Some(recipient.clone()),
+                ..Self::new_standard_package(env, &operator, id, amount, &token, expires_at)
+            };
+
+            // Validate policy, solvency and the operator's allowance
+            let check = Self::check_new_package(env, &config, &package).and_then(|_| {
+                Self::funding_checks(
+                    amount,
+                    contract_balance - current_locked,
+                    allowance - total_amount,
+                )
+                .into_iter()
+                .collect::<Result<(), Error>>()
+            });
+            if let Err(error) = check {
+                if !best_effort {
+                    return Err(error);
+                }
+                results.push_back(BatchCreateResult::Failed(error as u32));
+                continue;
+            }
+            counter = id.checked_add(1).ok_or(Error::PackageIdOutOfRange)?;
+
+            // Store package and track its aggregation index
+            Self::insert_package(env, &package, idx);
+            idx += 1;
+
+            // Update locked
+            current_locked += amount;
+            total_amount += amount;
+
+            // Emit per-package event
+            PackageCreatedEvent {
+                id,
+                recipient: Some(recipient),
+                amount,
+            }
+            .publish(env);
+
+            created_ids.push_back(id);
+            results.push_back(BatchCreateResult::Created(id));
+        }
+
+        Self::consume_allowance(env, &operator, &token, total_amount)?;
+
+        // Persist updated locked map, counter, and aggregation index
+        locked_map.set(token.clone(), current_locked);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalLocked, &locked_map);
+        env.storage()
+            .instance()
+            .set(&DataKey::PackageCounter, &counter);
+        env.storage()
+            .instance()
+            .set(&DataKey::PackageIndexCount, &idx);
+
+        // Emit batch event
+        BatchCreatedEvent {
+            ids: created_ids.clone(),
+            admin: operator,
+            total_amount,
+        }
+        .publish(env);
+
+        Ok((created_ids, results))
+    }
+
+    /// How much more `operator` may lock in `token`; unlimited for an exempt
+    /// admin.
+    fn remaining_allowance(env: &Env, operator: &Address, token: &Address) -> Result<i128, Error> {
+        if Self::is_allowance_exempt(env, operator)? {
+            return Ok(i128::MAX);
+        }
+        Ok(
+            Self::get_distributor_allowance(env.clone(), operator.clone(), token.clone())
+                .map_or(0, |allowance| allowance.remaining),
+        )
+    }
+
+    /// The `config` checks on anything that locks pool funds: amount, token
+    /// and, for locks with a lifetime, expiry.
+    fn config_checks(
+        env: &Env,
+        config: &Config,
+        amount: i128,
+        token: &Address,
+        expires_at: Option<u64>,
+    ) -> [Result<(), Error>; 3] {
+        let amount_check = if amount <= 0 || amount < config.min_amount {
+            Err(Error::InvalidAmount)
+        } else {
+            Ok(())
+        };
+
+        let token_check = if !config.allowed_tokens.is_empty()
+            && !config.allowed_tokens.contains(token.clone())
+        {
+            Err(Error::InvalidState)
+        } else {
+            Ok(())
+        };
+
+        let now = env.ledger().timestamp();
+        let expiry_check = match expires_at {
+            Some(expires_at)
+                if config.max_expires_in > 0
+                    && (expires_at == 0
+                        || expires_at <= now
+                        || expires_at - now > config.max_expires_in) =>
+            {
+                Err(Error::InvalidState)
+            }
+            _ => Ok(()),
+        };
+
+        [amount_check, token_check, expiry_check]
+    }
+
+    /// Checks a new package against `config` and the rules every package must
+    /// meet. All creation paths run this before locking funds, so policy is
+    /// enforced the same way whichever entrypoint is used.
+    fn check_new_package(env: &Env, config: &Config, package: &Package) -> Result<(), Error> {
+        Self::package_checks(env, config, package)
+            .into_iter()
+            .collect()
+    }
+
+    /// Each policy check on a new package, in the order creation applies
+    /// them: amount, token, expiry, schedule and ID uniqueness.
+    fn package_checks(env: &Env, config: &Config, package: &Package) -> [Result<(), Error>; 5] {
+        let expires_at = package.expires_at;
+        let [amount_check, token_check, expiry_check] = Self::config_checks(
+            env,
+            config,
+            package.amount,
+            &package.token,
+            Some(expires_at),
+        );
+
+        // The schedule must be complete before the package expires
+        let schedule_end = match &package.kind {
+            PackageKind::Standard | PackageKind::HashLocked(_) => Ok(None),
+            PackageKind::Vesting(schedule) => {
+                if schedule.start >= schedule.end
+                    || schedule.cliff < schedule.start
+                    || schedule.cliff > schedule.end
+                {
+                    Err(Error::InvalidState)
+                } else {
+                    Ok(Some(schedule.end))
+                }
+            }
+            PackageKind::Recurring(schedule) => {
+                if schedule.period == 0 || schedule.periods == 0 {
+                    Err(Error::InvalidState)
+                } else {
+                    (schedule.periods as u64 - 1)
+                        .checked_mul(schedule.period)
+                        .and_then(|offset| schedule.start.checked_add(offset))
+                        .map(Some)
+                        .ok_or(Error::InvalidState)
+                }
+            }
+        };
+        let schedule_check = schedule_end.and_then(|end| match end {
+            Some(end) if expires_at > 0 && expires_at < end => Err(Error::InvalidState),
+            _ => Ok(()),
+        });
+
+        // ID must be unused
+        let id_check = if env
+            .storage()
+            .persistent()
+            .has(&DataKey::Package(package.id))
+        {
+            Err(Error::PackageIdExists)
+        } else {
+            Ok(())
+        };
+
+        [
+            amount_check,
+            token_check,
+            expiry_check,
+            schedule_check,
+            id_check,
+        ]
+    }
+
+    /// The checks on who may create packages, without requiring auth.
+    fn operator_checks(env: &Env, operator: &Address) -> Result<[Result<(), Error>; 2], Error> {
+        let admin = Self::get_admin(env.clone())?;
+        let role_check = if *operator == admin {
+            if env.storage().instance().has(&DataKey::Council) {
+                Err(Error::CouncilRequired)
+            } else {
+                Ok(())
+            }
+        } else if Self::has_role(env.clone(), Role::Distributor, operator.clone()) {
+            Ok(())
+        } else {
+            Err(Error::NotAuthorized)
+        };
+        Ok([Self::check_paused(env), role_check])
+    }
+
+    /// A `Created` standard package as the creation entrypoints build it.
+    fn new_standard_package(
+        env: &Env,
+        operator: &Address,
+        id: u64,
+        amount: i128,
+        token: &Address,
+        expires_at: u64,
+    ) -> Package {
+        Package {
+            id,
+            recipient: None,
+            amount,
+            claimed_amount: 0,
+            token: token.clone(),
+            status: PackageStatus::Created,
+            created_at: env.ledger().timestamp(),
+            expires_at,
+            metadata: Map::new(env),
+            campaign_id: None,
+            created_by: operator.clone(),
+            kind: PackageKind::Standard,
+            previous_recipients: Vec::new(env),
+        }
+    }
+
+    /// The contract's `token` balance not locked by packages or budgets.
+    fn available_balance(env: &Env, token: &Address) -> i128 {
+        let token_client = token::Client::new(env, token);
+        let contract_balance = token_client.balance(&env.current_contract_address());
+        let locked_map: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalLocked)
+            .unwrap_or(Map::new(env));
+        contract_balance - locked_map.get(token.clone()).unwrap_or(0)
+    }
+
+    /// Checks that `amount` fits in the unlocked pool (`available`) and in
+    /// what is left of the operator's allowance.
+    fn funding_checks(amount: i128, available: i128, allowance: i128) -> [Result<(), Error>; 2] {
+        [
+            if amount > available {
+                Err(Error::InsufficientFunds)
+            } else {
+                Ok(())
+            },
+            if amount > allowance {
+                Err(Error::AllowanceExceeded)
+            } else {
+                Ok(())
+            },
+        ]
+    }
+
+    /// `Error` codes of the failed checks.
+    fn error_codes(env: &Env, checks: impl IntoIterator<Item = Result<(), Error>>) -> Vec<u32> {
+        let mut codes = Vec::new(env);
+        for check in checks {
+            if let Err(error) = check {
+                codes.push_back(error as u32);
+            }
+        }
+        codes
+    }
+
+    /// Validates and stores a new `Created` package, locking its amount from the pool.
+    fn create_package_internal(env: &Env, package: Package) -> Result<u64, Error> {
+        let config = Self::get_config(env.clone());
+        let id = package.id;
+        let amount = package.amount;
+        if id > MAX_EXPLICIT_PACKAGE_ID {
+            return Err(Error::PackageIdOutOfRange);
+        }
+
+        // 1. Check policy and ID uniqueness
+        Self::check_new_package(env, &config, &package)?;
+
+        // 2. Check Solvency and update Locked State
+        Self::lock_funds(env, &package.token, amount)?;
+
+        // 3. Store package and track its aggregation index
+        let idx: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PackageIndexCount)
+            .unwrap_or(0);
+        Self::insert_package(env, &package, idx);
+        env.storage()
+            .instance()
+            .set(&DataKey::PackageIndexCount, &(idx + 1));
+
+        // 4. Keep batch numbering past manually chosen IDs
+        let counter: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PackageCounter)
+            .unwrap_or(0);
+        if id >= counter {
+            env.storage()
+                .instance()
+                .set(&DataKey::PackageCounter, &(id + 1));
+        }
+
+        // Emit Event
+        PackageCreatedEvent {
+            id,
+            recipient: package.recipient,
+            amount,
+        }
+        .publish(env);
+
+        Ok(id)
+    }
+
+    /// Admin auth for operations that require council approval once a council
+    /// is configured.
+    fn require_sole_admin(env: &Env) -> Result<Address, Error> {
+        let admin = Self::get_admin(env.clone())?;
+        if env.storage().instance().has(&DataKey::Council) {
+            return Err(Error::CouncilRequired);
+        }
+        admin.require_auth();
+        Ok(admin)
+    }
+
+    fn store_council(env: &Env, council: Council) -> Result<(), Error> {
+        if council.threshold == 0 || council.threshold > council.signers.len() {
+            return Err(Error::InvalidThreshold);
+        }
+        env.storage().instance().set(&DataKey::Council, &council);
+
+        CouncilUpdatedEvent {
+            signers: council.signers,
+            threshold: council.threshold,
+        }
+        .publish(env);
+
+        Ok(())
+    }
+
+    /// Rejects direct calls to timelocked operations while the timelock is enabled.
+    fn check_timelock(env: &Env) -> Result<(), Error> {
+        if Self::get_timelock_delay(env.clone()) > 0 {
+            return Err(Error::TimelockRequired);
+        }
+        Ok(())
+    }
+
+    fn queue_operation_internal(env: &Env, op: TimelockOp) -> u64 {
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::OperationCounter)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::OperationCounter, &(id + 1));
+
+        let now = env.ledger().timestamp();
+        let eta = now + Self::get_timelock_delay(env.clone());
+        let operation = QueuedOperation {
+            id,
+            op: op.clone(),
+            queued_at: now,
+            eta,
+            status: OperationStatus::Queued,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Operation(id), &operation);
+
+        OperationQueuedEvent { id, op, eta }.publish(env);
+
+        id
+    }
+
+    fn cancel_operation_internal(env: &Env, admin: Address, op_id: u64) -> Result<(), Error> {
+        let mut operation = Self::get_operation(env.clone(), op_id)?;
+        if operation.status != OperationStatus::Queued {
+            return Err(Error::OperationNotQueued);
+        }
+
+        operation.status = OperationStatus::Cancelled;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Operation(op_id), &operation);
+
+        OperationCancelledEvent { id: op_id, admin }.publish(env);
+
+        Ok(())
+    }
+
+    fn set_timelock_delay_internal(env: &Env, delay: u64) -> Result<(), Error> {
+        if delay > MAX_TIMELOCK_DELAY {
+            return Err(Error::InvalidState);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::TimelockDelay, &delay);
+        Ok(())
+    }
+
+    fn set_allowance_internal(
+        env: &Env,
+        distributor: Address,
+        token: Address,
+        amount: i128,
+        refill_amount: i128,
+        refill_period: u64,
+    ) -> Result<(), Error> {
+        if amount < 0 || refill_amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let allowance = DistributorAllowance {
+            remaining: amount,
+            refill_amount,
+            refill_period,
+            period_start: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(
+            &DataKey::Allowance(distributor.clone(), token.clone()),
+            &allowance,
+        );
+
+        AllowanceSetEvent {
+            distributor,
+            token,
+            amount,
+            refill_amount,
+            refill_period,
+        }
+        .publish(env);
+
+        Ok(())
+    }
+
+    fn propose_admin_internal(
+        env: &Env,
+        admin: Address,
+        new_admin: Address,
+        expires_at: u64,
+    ) -> Result<(), Error> {
+        let now = env.ledger().timestamp();
+        if expires_at > 0 && expires_at <= now {
+            return Err(Error::InvalidState);
+        }
+
+        let pending = PendingAdmin {
+            new_admin: new_admin.clone(),
+            proposed_at: now,
+            expires_at,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::PendingAdmin, &pending);
+
+        AdminTransferProposedEvent {
+            admin,
+            new_admin,
+            expires_at,
+        }
+        .publish(env);
+
+        Ok(())
+    }
 
-        if amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
+    fn cancel_admin_transfer_internal(env: &Env, admin: Address) -> Result<(), Error> {
+        let pending = Self::get_pending_admin(env.clone()).ok_or(Error::NoPendingAdmin)?;
+        env.storage().instance().remove(&DataKey::PendingAdmin);
 
-        if amount < config.min_amount {
-            return Err(Error::InvalidAmount);
+        AdminTransferCancelledEvent {
+            admin,
+            new_admin: pending.new_admin,
         }
+        .publish(env);
 
-        if !config.allowed_tokens.is_empty() && !config.allowed_tokens.contains(token.clone()) {
-            return Err(Error::InvalidState);
+        Ok(())
+    }
+
+    fn grant_role_internal(env: &Env, admin: Address, role: Role, account: Address) {
+        let mut members = Self::list_role_members(env.clone(), role);
+        if !members.contains(account.clone()) {
+            members.push_back(account.clone());
+            env.storage()
+                .instance()
+                .set(&DataKey::RoleMembers(role), &members);
         }
 
-        if config.max_expires_in > 0 {
-            let now = env.ledger().timestamp();
-            if expires_at == 0 || expires_at <= now || expires_at - now > config.max_expires_in {
-                return Err(Error::InvalidState);
-            }
+        RoleGrantedEvent {
+            role,
+            account,
+            admin,
         }
+        .publish(env);
+    }
 
-        // 1. Check ID Uniqueness
-        let key = (symbol_short!("pkg"), id);
-        if env.storage().persistent().has(&key) {
-            return Err(Error::PackageIdExists);
+    fn revoke_role_internal(
+        env: &Env,
+        admin: Address,
+        role: Role,
+        account: Address,
+    ) -> Result<(), Error> {
+        let mut members = Self::list_role_members(env.clone(), role);
+        if let Some(index) = members.first_index_of(account.clone()) {
+            members.remove(index);
+            env.storage()
+                .instance()
+                .set(&DataKey::RoleMembers(role), &members);
         }
 
-        // 2. Check Solvency (Available Balance vs Locked)
-        let token_client = token::Client::new(&env, &token);
-        let contract_balance = token_client.balance(&env.current_contract_address());
+        RoleRevokedEvent {
+            role,
+            account,
+            admin,
+        }
+        .publish(env);
 
-        let mut locked_map: Map<Address, i128> = env
-            .storage()
-            .instance()
-            .get(&KEY_TOTAL_LOCKED)
-            .unwrap_or(Map::new(&env));
-        let current_locked = locked_map.get(token.clone()).unwrap_or(0);
+        Ok(())
+    }
 
-        // Ensure we don't over-promise funds
-        if contract_balance < current_locked + amount {
-            return Err(Error::InsufficientFunds);
-        }
+    /// Stores a new package at aggregation index `idx` with a full TTL.
+    fn insert_package(env: &Env, package: &Package, idx: u64) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Package(package.id), package);
+        Self::insert_records(env, package, idx);
+        Self::update_aggregates(env, None, package);
+    }
 
-        // 3. Update Locked State
-        locked_map.set(token.clone(), current_locked + amount);
-        env.storage().instance().set(&KEY_TOTAL_LOCKED, &locked_map);
+    /// Adds a stored package to the aggregation and recipient indexes and
+    /// gives all of its entries a full TTL.
+    fn insert_records(env: &Env, package: &Package, idx: u64) {
+        let persistent = env.storage().persistent();
+        persistent.set(&DataKey::PackageIndex(idx), &package.id);
 
-        // 4. Create Package
-        let created_at = env.ledger().timestamp();
-        let package = Package {
-            id,
-            recipient: recipient.clone(),
-            amount,
-            token: token.clone(),
-            status: PackageStatus::Created,
-            created_at,
-            expires_at,
-            metadata: Map::new(&env),
+        let recipient_index = match &package.recipient {
+            Some(recipient) => Self::index_recipient(env, recipient, package.id),
+            None => 0,
         };
 
-        env.storage().persistent().set(&key, &package);
+        persistent.set(
+            &DataKey::PackageTtl(package.id),
+            &PackageTtl {
+                id: package.id,
+                index: idx,
+                recipient_index,
+                live_until: 0,
+            },
+        );
+        Self::bump_package(env, package.id);
+    }
 
-        // 5. Track package index for aggregation
-        let idx: u64 = env.storage().instance().get(&KEY_PKG_IDX).unwrap_or(0);
-        let idx_key = (symbol_short!("pidx"), idx);
-        env.storage().persistent().set(&idx_key, &id);
-        env.storage().instance().set(&KEY_PKG_IDX, &(idx + 1));
+    /// Appends a package to a recipient's index and returns its position.
+    fn index_recipient(env: &Env, recipient: &Address, id: u64) -> u64 {
+        let persistent = env.storage().persistent();
+        let count_key = DataKey::RecipientPackageCount(recipient.clone());
+        let position: u64 = persistent.get(&count_key).unwrap_or(0);
+        persistent.set(&DataKey::RecipientPackage(recipient.clone(), position), &id);
+        persistent.set(&count_key, &(position + 1));
+        position
+    }
 
-        // Emit Event
-        PackageCreatedEvent {
-            id,
-            recipient,
-            amount,
+    /// Sets the recipient of a package and indexes it, dropping it from the
+    /// previous recipient's index. The package's entries are re-extended
+    /// when it is next saved.
+    fn assign_recipient(env: &Env, package: &mut Package, recipient: &Address) {
+        let persistent = env.storage().persistent();
+        let ttl_key = DataKey::PackageTtl(package.id);
+        if let Some(mut ttl) = persistent.get::<_, PackageTtl>(&ttl_key) {
+            if let Some(previous) = &package.recipient {
+                persistent.remove(&DataKey::RecipientPackage(
+                    previous.clone(),
+                    ttl.recipient_index,
+                ));
+            }
+            ttl.recipient_index = Self::index_recipient(env, recipient, package.id);
+            ttl.live_until = 0;
+            persistent.set(&ttl_key, &ttl);
         }
-        .publish(&env);
-
-        Ok(id)
+        package.recipient = Some(recipient.clone());
     }
 
-    /// Creates multiple packages in a single transaction for multiple recipients.
-    /// Uses an auto-incrementing counter for package IDs.
-    pub fn batch_create_packages(
-        env: Env,
-        operator: Address,
-        recipients: Vec<Address>,
-        amounts: Vec<i128>,
-        token: Address,
-        expires_in: u64,
-    ) -> Result<Vec<u64>, Error> {
-        Self::check_paused(&env)?;
-        Self::require_admin_or_distributor(&env, &operator)?;
+    /// Writes back an existing package, extending its TTL if it is running low.
+    /// Aggregates follow from the difference to the stored version.
+    fn save_package(env: &Env, package: &Package) {
+        let key = DataKey::Package(package.id);
+        let previous: Option<Package> = env.storage().persistent().get(&key);
+        env.storage().persistent().set(&key, package);
+        if let Some(previous) = previous {
+            Self::update_aggregates(env, Some(&previous), package);
+        }
+        Self::bump_package(env, package.id);
+    }
 
-        // Validate array lengths match
-        if recipients.len() != amounts.len() {
-            return Err(Error::MismatchedArrays);
+    /// Moves a package's contribution to its token's aggregates from
+    /// `previous` (`None` for a new package) to `current`.
+    fn update_aggregates(env: &Env, previous: Option<&Package>, current: &Package) {
+        if previous.is_some_and(|previous| {
+            previous.status == current.status && previous.claimed_amount == current.claimed_amount
+        }) || !Self::is_aggregated(env, current.id)
+        {
+            return;
         }
 
-        let token_client = token::Client::new(&env, &token);
-        let contract_balance = token_client.balance(&env.current_contract_address());
+        let key = DataKey::Aggregates(current.token.clone());
+        let mut aggregates = Self::get_aggregates(env.clone(), current.token.clone());
+        if let Some(previous) = previous {
+            Self::tally(&mut aggregates, previous, false);
+        }
+        Self::tally(&mut aggregates, current, true);
+        Self::store_aggregates(env, &key, &aggregates);
+    }
 
-        let mut locked_map: Map<Address, i128> = env
+    /// Whether a package is already reflected in the stored aggregates. While
+    /// a rebuild is pending, packages it has not reached yet are not.
+    fn is_aggregated(env: &Env, id: u64) -> bool {
+        let Some(rebuild) = env
             .storage()
             .instance()
-            .get(&KEY_TOTAL_LOCKED)
-            .unwrap_or(Map::new(&env));
-        let mut current_locked = locked_map.get(token.clone()).unwrap_or(0);
+            .get::<_, AggregatesRebuild>(&DataKey::AggregatesRebuild)
+        else {
+            return true;
+        };
+        match env
+            .storage()
+            .persistent()
+            .get::<_, PackageTtl>(&DataKey::PackageTtl(id))
+        {
+            Some(ttl) => ttl.index < rebuild.next || ttl.index >= rebuild.end,
+            None => false,
+        }
+    }
 
-        // Read the current package counter
-        let mut counter: u64 = env.storage().instance().get(&KEY_PKG_COUNTER).unwrap_or(0);
-        // Read the current aggregation index
-        let mut idx: u64 = env.storage().instance().get(&KEY_PKG_IDX).unwrap_or(0);
+    fn tally(aggregates: &mut Aggregates, package: &Package, add: bool) {
+        let sign: i128 = if add { 1 } else { -1 };
+        aggregates.total_claimed += sign * package.claimed_amount;
+        let count = match package.status {
+            PackageStatus::Created | PackageStatus::PartiallyClaimed => {
+                aggregates.total_committed += sign * package.remaining();
+                &mut aggregates.committed_count
+            }
+            PackageStatus::Claimed => &mut aggregates.claimed_count,
+            PackageStatus::Expired | PackageStatus::Cancelled | PackageStatus::Refunded => {
+                aggregates.total_expired_cancelled += sign * package.remaining();
+                &mut aggregates.expired_cancelled_count
+            }
+        };
+        if add {
+            *count += 1;
+        } else {
+            *count -= 1;
+        }
+    }
 
-        let created_at = env.ledger().timestamp();
-        let expires_at = created_at + expires_in;
+    fn store_aggregates(env: &Env, key: &DataKey, aggregates: &Aggregates) {
+        let persistent = env.storage().persistent();
+        persistent.set(key, aggregates);
+        persistent.extend_ttl(key, PACKAGE_TTL_THRESHOLD, PACKAGE_TTL_EXTEND);
+    }
 
-        let mut created_ids: Vec<u64> = Vec::new(&env);
-        let mut total_amount: i128 = 0;
+    fn bump_package(env: &Env, id: u64) {
+        let persistent = env.storage().persistent();
+        let ttl_key = DataKey::PackageTtl(id);
+        let Some(mut ttl) = persistent.get::<_, PackageTtl>(&ttl_key) else {
+            return;
+        };
 
-        for i in 0..recipients.len() {
-            let recipient = recipients.get(i).unwrap();
-            let amount = amounts.get(i).unwrap();
+        let now = env.ledger().sequence();
+        if ttl.live_until.saturating_sub(now) >= PACKAGE_TTL_THRESHOLD {
+            return;
+        }
 
-            // Validate amount
-            if amount <= 0 {
-                return Err(Error::InvalidAmount);
+        // Extend unconditionally so `live_until` stays a lower bound even if
+        // the entries were extended externally in the meantime.
+        let Some(package) = persistent.get::<_, Package>(&DataKey::Package(id)) else {
+            return;
+        };
+        ttl.live_until = now + PACKAGE_TTL_EXTEND;
+        persistent.set(&ttl_key, &ttl);
+        for key in [
+            DataKey::Package(id),
+            DataKey::PackageIndex(ttl.index),
+            ttl_key,
+        ] {
+            persistent.extend_ttl(&key, PACKAGE_TTL_EXTEND, PACKAGE_TTL_EXTEND);
+        }
+        if let Some(recipient) = package.recipient {
+            for key in [
+                DataKey::RecipientPackage(recipient.clone(), ttl.recipient_index),
+                DataKey::RecipientPackageCount(recipient),
+            ] {
+                persistent.extend_ttl(&key, PACKAGE_TTL_EXTEND, PACKAGE_TTL_EXTEND);
             }
+        }
+        Self::bump_instance_ttl(env);
+    }
 
-            // Check solvency
-            if contract_balance < current_locked + amount {
-                return Err(Error::InsufficientFunds);
-            }
+    fn bump_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND);
+    }
 
-            // Assign ID and increment counter
-            let id = counter;
-            counter += 1;
+    fn upgrade_internal(env: &Env, wasm_hash: BytesN<32>) {
+        env.deployer()
+            .update_current_contract_wasm(wasm_hash.clone());
+        ContractUpgradedEvent { wasm_hash }.publish(env);
+    }
 
-            let key = (symbol_short!("pkg"), id);
+    /// Moves the v1 instance entries to their current keys and records the
+    /// migration and aggregate rebuild over the v1 index positions.
+    fn migrate_instance(env: &Env) -> MigrationProgress {
+        let instance = env.storage().instance();
+
+        let instance_keys = [
+            (V1_KEY_ADMIN, DataKey::Admin),
+            (V1_KEY_TOTAL_LOCKED, DataKey::TotalLocked),
+            (V1_KEY_PKG_COUNTER, DataKey::PackageCounter),
+            (V1_KEY_CONFIG, DataKey::Config),
+            (V1_KEY_PKG_IDX, DataKey::PackageIndexCount),
+            (V1_KEY_PAUSED, DataKey::Paused),
+        ];
+        for (old, new) in instance_keys {
+            if let Some(value) = instance.get::<_, Val>(&old) {
+                instance.set(&new, &value);
+                instance.remove(&old);
+            }
+        }
 
-            // Create package
-            let package = Package {
-                id,
-                recipient: recipient.clone(),
-                amount,
-                token: token.clone(),
-                status: PackageStatus::Created,
-                created_at,
-                expires_at,
-                metadata: Map::new(&env),
-            };
+        // v1 kept distributors as an address -> enabled map
+        if let Some(distributors) = instance.get::<_, Map<Address, bool>>(&V1_KEY_DISTRIBUTORS) {
+            let mut members: Vec<Address> = Vec::new(env);
+            for (account, enabled) in distributors.iter() {
+                if enabled {
+                    members.push_back(account);
+                }
+            }
+            instance.set(&DataKey::RoleMembers(Role::Distributor), &members);
+            instance.remove(&V1_KEY_DISTRIBUTORS);
+        }
 
-            env.storage().persistent().set(&key, &package);
+        let end: u64 = instance.get(&DataKey::PackageIndexCount).unwrap_or(0);
+        let progress = MigrationProgress { next: 0, end };
+        instance.set(&DataKey::Migration, &progress);
+        if end > 0 {
+            instance.set(
+                &DataKey::AggregatesRebuild,
+                &AggregatesRebuild { next: 0, end },
+            );
+        }
+        progress
+    }
 
-            // Track package index for aggregation
-            let idx_key = (symbol_short!("pidx"), idx);
-            env.storage().persistent().set(&idx_key, &id);
-            idx += 1;
+    /// Moves the package at v1 index position `idx` to the current keys,
+    /// filling in the fields added since v1, and builds its TTL record and
+    /// recipient index entry.
+    fn migrate_package(env: &Env, admin: &Address, idx: u64) {
+        let persistent = env.storage().persistent();
+        let old_idx = (V1_KEY_PIDX, idx);
+        let Some(id) = persistent.get::<_, u64>(&old_idx) else {
+            return;
+        };
+        persistent.remove(&old_idx);
+        persistent.set(&DataKey::PackageIndex(idx), &id);
 
-            // Update locked
-            current_locked += amount;
-            total_amount += amount;
+        let old_pkg = (V1_KEY_PKG, id);
+        let Some(mut fields) = persistent.get::<_, Map<Symbol, Val>>(&old_pkg) else {
+            return;
+        };
+        persistent.remove(&old_pkg);
 
-            // Emit per-package event
-            PackageCreatedEvent {
-                id,
-                recipient,
-                amount,
-            }
-            .publish(&env);
+        let status = fields.get(Symbol::new(env, "status")).unwrap();
+        let amount = fields.get(Symbol::new(env, "amount")).unwrap();
+        let claimed: i128 =
+            if PackageStatus::try_from_val(env, &status) == Ok(PackageStatus::Claimed) {
+                i128::try_from_val(env, &amount).unwrap()
+            } else {
+                0
+            };
+        fields.set(Symbol::new(env, "claimed_amount"), claimed.into_val(env));
+        fields.set(
+            Symbol::new(env, "campaign_id"),
+            Option::<u64>::None.into_val(env),
+        );
+        fields.set(Symbol::new(env, "created_by"), admin.into_val(env));
+        fields.set(
+            Symbol::new(env, "kind"),
+            PackageKind::Standard.into_val(env),
+        );
+        fields.set(
+            Symbol::new(env, "previous_recipients"),
+            Vec::<Address>::new(env).into_val(env),
+        );
 
-            created_ids.push_back(id);
+        // v1 recipients are plain addresses, which decode as `Some`
+        let package = Package::try_from_val(env, &fields.to_val()).unwrap();
+        persistent.set(&DataKey::Package(id), &package);
+        Self::insert_records(env, &package, idx);
+    }
+
+    fn set_config_internal(env: &Env, config: Config) -> Result<(), Error> {
+        if config.min_amount <= 0 {
+            return Err(Error::InvalidAmount);
         }
 
-        // Persist updated locked map, counter, and aggregation index
-        locked_map.set(token.clone(), current_locked);
-        env.storage().instance().set(&KEY_TOTAL_LOCKED, &locked_map);
-        env.storage().instance().set(&KEY_PKG_COUNTER, &counter);
-        env.storage().instance().set(&KEY_PKG_IDX, &idx);
+        env.storage().instance().set(&DataKey::Config, &config);
+        Ok(())
+    }
 
-        // Emit batch event
-        BatchCreatedEvent {
-            ids: created_ids.clone(),
-            admin: operator,
-            total_amount,
-        }
-        .publish(&env);
+    fn pause_internal(env: &Env, admin: Address) -> Result<(), Error> {
+        env.storage().instance().set(&DataKey::Paused, &true);
+        ContractPausedEvent { admin }.publish(env);
+        Ok(())
+    }
 
-        Ok(created_ids)
+    fn unpause_internal(env: &Env, admin: Address) -> Result<(), Error> {
+        env.storage().instance().set(&DataKey::Paused, &false);
+        ContractUnpausedEvent { admin }.publish(env);
+        Ok(())
     }
 
-    // --- Recipient Actions ---
+    /// Runs `op` and applies the token movements it recorded.
+    fn settle(
+        env: &Env,
+        op: impl FnOnce(&mut Settlement) -> Result<i128, Error>,
+    ) -> Result<(), Error> {
+        let mut settlement = Settlement::new(env);
+        op(&mut settlement)?;
+        settlement.apply(env);
+        Ok(())
+    }
 
-    /// Recipient claims the package.
-    pub fn claim(env: Env, id: u64) -> Result<(), Error> {
-        Self::check_paused(&env)?;
-        let key = (symbol_short!("pkg"), id);
-        let mut package: Package = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .ok_or(Error::PackageNotFound)?;
+    /// Runs `op` on every id, recording per-item outcomes, then applies the
+    /// combined token movements and emits a summary event. `op` must leave a
+    /// package untouched when it fails.
+    fn run_batch(
+        env: &Env,
+        action: Symbol,
+        ids: Vec<u64>,
+        mut op: impl FnMut(&mut Settlement, u64) -> Result<i128, Error>,
+    ) -> Vec<BatchItemResult> {
+        let mut settlement = Settlement::new(env);
+        let mut results = Vec::new(env);
+        let mut succeeded: u32 = 0;
+        let mut total_amount: i128 = 0;
 
-        // Validations
-        if package.status != PackageStatus::Created {
-            return Err(Error::PackageNotActive);
+        for id in ids.iter() {
+            let outcome = match op(&mut settlement, id) {
+                Ok(amount) => {
+                    succeeded += 1;
+                    total_amount += amount;
+                    BatchOutcome::Ok(amount)
+                }
+                Err(error) => BatchOutcome::Failed(error as u32),
+            };
+            results.push_back(BatchItemResult { id, outcome });
         }
-        // Check expiry
-        if package.expires_at > 0 && env.ledger().timestamp() > package.expires_at {
-            // Auto-expire if accessed after date
-            package.status = PackageStatus::Expired;
-            env.storage().persistent().set(&key, &package);
-            return Err(Error::PackageExpired);
+        settlement.apply(env);
+
+        BatchProcessedEvent {
+            action,
+            succeeded,
+            failed: results.len() - succeeded,
+            total_amount,
         }
+        .publish(env);
+
+        results
+    }
+
+    /// Pays `amount` (the full remainder if `None`) to the recipient.
+    fn claim_internal(
+        env: &Env,
+        settlement: &mut Settlement,
+        id: u64,
+        amount: Option<i128>,
+        destination: Option<Address>,
+    ) -> Result<i128, Error> {
+        let mut package = Self::load_claimable(env, id)?;
+        // Hash-locked packages are claimed with their secret
+        let recipient = package.recipient.clone().ok_or(Error::InvalidState)?;
+
+        let available = package.available(env.ledger().timestamp());
+        let payout = match amount {
+            None if available > 0 => available,
+            None => return Err(Error::NothingToClaim),
+            Some(amount) if amount > 0 && amount <= available => amount,
+            Some(_) => return Err(Error::InvalidAmount),
+        };
 
         // Auth
-        package.recipient.require_auth();
+        recipient.require_auth();
 
-        // State Transition: Created -> Claimed
-        // Checks passed, update state FIRST (Re-entrancy protection)
-        package.status = PackageStatus::Claimed;
-        env.storage().persistent().set(&key, &package);
+        let to = destination.unwrap_or(recipient);
+        Self::apply_claim(env, settlement, &mut package, payout, &to);
+        Ok(payout)
+    }
 
-        // Update Global Locked
-        Self::decrement_locked(&env, &package.token, package.amount);
+    fn reassign_internal(
+        env: &Env,
+        admin: Address,
+        id: u64,
+        new_recipient: Address,
+        reason: String,
+        with_consent: bool,
+    ) -> Result<(), Error> {
+        let mut package = Self::get_package(env.clone(), id)?;
+        if package.status != PackageStatus::Created {
+            return Err(Error::InvalidState);
+        }
+        // Hash-locked packages are only ever assigned by their secret
+        let old_recipient = package.recipient.clone().ok_or(Error::InvalidState)?;
+        if old_recipient == new_recipient {
+            return Err(Error::InvalidState);
+        }
+        if with_consent {
+            old_recipient.require_auth();
+        }
 
-        // Effect: Transfer Funds
-        let token_client = token::Client::new(&env, &package.token);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &package.recipient,
-            &package.amount,
-        );
+        Self::assign_recipient(env, &mut package, &new_recipient);
+        package.previous_recipients.push_back(old_recipient.clone());
+        Self::save_package(env, &package);
 
-        // Emit Event
-        ClaimedEvent {
+        RecipientReassignedEvent {
             id,
-            recipient: package.recipient.clone(),
-            amount: package.amount,
+            old_recipient,
+            new_recipient,
+            reason,
+            admin,
         }
-        .publish(&env);
+        .publish(env);
 
         Ok(())
     }
 
-    // --- Admin Actions ---
+    /// Storage key for a recipient's delegation. A per-package delegation can
+    /// only be managed by the package's recipient.
+    fn delegate_key(
+        env: &Env,
+        recipient: &Address,
+        package_id: Option<u64>,
+    ) -> Result<DataKey, Error> {
+        let Some(id) = package_id else {
+            return Ok(DataKey::RecipientDelegate(recipient.clone()));
+        };
+        let package = Self::get_package(env.clone(), id)?;
+        if package.recipient.as_ref() != Some(recipient) {
+            return Err(Error::NotAuthorized);
+        }
+        Ok(DataKey::PackageDelegate(id))
+    }
 
-    /// Admin manually triggers disbursement (overrides recipient claim need, strictly checks status).
-    pub fn disburse(env: Env, id: u64) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
+    /// Loads a package whose metadata `caller` may edit: the admin or the
+    /// package's creator.
+    fn load_for_metadata(env: &Env, caller: &Address, id: u64) -> Result<Package, Error> {
+        caller.require_auth();
+        let package = Self::get_package(env.clone(), id)?;
+        if *caller == Self::get_admin(env.clone())? {
+            return Ok(package);
+        }
+        // A creator that has since lost the role can no longer annotate
+        if *caller != package.created_by
+            || !Self::has_role(env.clone(), Role::Distributor, caller.clone())
+        {
+            return Err(Error::NotAuthorized);
+        }
+        Ok(package)
+    }
 
-        let key = (symbol_short!("pkg"), id);
-        let mut package: Package = env
+    /// Whether `value` is a 64-character hex string.
+    fn is_hex_digest(value: &String) -> bool {
+        if value.len() != 64 {
+            return false;
+        }
+        let mut buf = [0u8; 64];
+        value.copy_into_slice(&mut buf);
+        buf.iter().all(u8::is_ascii_hexdigit)
+    }
+
+    /// Loads a package for claiming: the contract must not be paused and the
+    /// package must be active and unexpired.
+    fn load_claimable(env: &Env, id: u64) -> Result<Package, Error> {
+        Self::check_paused(env)?;
+        let key = DataKey::Package(id);
+        let package: Package = env
             .storage()
             .persistent()
             .get(&key)
             .ok_or(Error::PackageNotFound)?;
 
-        if package.status != PackageStatus::Created {
+        // Validations
+        if !package.is_active() {
             return Err(Error::PackageNotActive);
         }
-
-        // State Transition
-        package.status = PackageStatus::Claimed;
-        env.storage().persistent().set(&key, &package);
-
-        // Update Locked
-        Self::decrement_locked(&env, &package.token, package.amount);
-
-        // Transfer
-        let token_client = token::Client::new(&env, &package.token);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &package.recipient,
-            &package.amount,
-        );
-
-        DisbursedEvent {
-            id,
-            admin: admin.clone(),
-            amount: package.amount,
+        // Check expiry. The package is left as is: `refund` marks it expired
+        // and unlocks its funds.
+        if package.expires_at > 0 && env.ledger().timestamp() > package.expires_at {
+            return Err(Error::PackageExpired);
         }
-        .publish(&env);
-
-        Ok(())
+        Ok(package)
     }
 
-    /// Admin revokes a package (Cancels it). Funds are effectively unlocked but remain in contract pool.
-    pub fn revoke(env: Env, id: u64) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
-
-        let key = (symbol_short!("pkg"), id);
-        let mut package: Package = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .ok_or(Error::PackageNotFound)?;
+    /// Records a validated claim of `payout` and transfers it to `to`.
+    fn apply_claim(
+        env: &Env,
+        settlement: &mut Settlement,
+        package: &mut Package,
+        payout: i128,
+        to: &Address,
+    ) {
+        // State Transition: Created -> PartiallyClaimed / Claimed
+        // Checks passed, update state FIRST (Re-entrancy protection)
+        package.claimed_amount += payout;
+        package.status = if package.remaining() == 0 {
+            PackageStatus::Claimed
+        } else {
+            PackageStatus::PartiallyClaimed
+        };
+        Self::save_package(env, package);
 
-        if package.status != PackageStatus::Created {
-            return Err(Error::InvalidState);
-        }
+        // Update Global Locked
+        settlement.unlock(&package.token, payout);
+        Self::release_campaign(env, package, payout, true);
 
-        // State Transition
-        package.status = PackageStatus::Cancelled;
-        env.storage().persistent().set(&key, &package);
+        // Effect: Transfer Funds
+        Self::pay_recipient(env, settlement, package, payout, to);
+    }
 
-        // Unlock funds (return to pool)
-        Self::decrement_locked(&env, &package.token, package.amount);
+    /// Pays `amount` of a package to `to`.
+    fn pay_recipient(
+        env: &Env,
+        settlement: &mut Settlement,
+        package: &Package,
+        amount: i128,
+        to: &Address,
+    ) {
+        settlement.pay(&package.token, to, amount);
 
-        RevokedEvent {
-            id,
-            admin: admin.clone(),
-            amount: package.amount,
+        ClaimedEvent {
+            id: package.id,
+            recipient: package.recipient.clone().unwrap_or_else(|| to.clone()),
+            destination: to.clone(),
+            amount,
         }
-        .publish(&env);
+        .publish(env);
+    }
 
-        Ok(())
+    /// Before a vesting or recurring package is cancelled, settles what has
+    /// been released but is unclaimed as claimed so only the unreleased part
+    /// returns to the pool.
+    /// Returns the amount to pay the recipient once the package is saved.
+    fn settle_vested(env: &Env, settlement: &mut Settlement, package: &mut Package) -> i128 {
+        if !matches!(
+            package.kind,
+            PackageKind::Vesting(_) | PackageKind::Recurring(_)
+        ) {
+            return 0;
+        }
+        let payout = package.available(env.ledger().timestamp());
+        if payout > 0 {
+            package.claimed_amount += payout;
+            settlement.unlock(&package.token, payout);
+            Self::release_campaign(env, package, payout, true);
+        }
+        payout
     }
 
-    pub fn refund(env: Env, id: u64) -> Result<(), Error> {
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
+    /// Status of a package being cancelled; fully vested ones end up claimed.
+    fn cancelled_status(package: &Package) -> PackageStatus {
+        if package.remaining() == 0 {
+            PackageStatus::Claimed
+        } else {
+            PackageStatus::Cancelled
+        }
+    }
 
-        let key = (symbol_short!("pkg"), id);
+    fn revoke_internal(
+        env: &Env,
+        settlement: &mut Settlement,
+        admin: &Address,
+        id: u64,
+    ) -> Result<i128, Error> {
+        let key = DataKey::Package(id);
         let mut package: Package = env
             .storage()
             .persistent()
             .get(&key)
             .ok_or(Error::PackageNotFound)?;
 
-        // Can only refund if Expired or Cancelled.
-        // If Created, must Revoke first. If Claimed, impossible.
-        // If Refunded, impossible.
-        if package.status == PackageStatus::Created {
-            // Check if actually expired
-            if package.expires_at > 0 && env.ledger().timestamp() > package.expires_at {
-                package.status = PackageStatus::Expired;
-                // If we just expired it, we need to unlock the funds first
-                Self::decrement_locked(&env, &package.token, package.amount);
-            } else {
-                return Err(Error::InvalidState);
-            }
-        } else if package.status == PackageStatus::Claimed
-            || package.status == PackageStatus::Refunded
-        {
+        if !package.is_active() {
             return Err(Error::InvalidState);
         }
 
-        // If Cancelled, funds were already unlocked in `revoke`.
-        // If Expired (logic above), funds were just unlocked.
-
         // State Transition
-        package.status = PackageStatus::Refunded;
-        env.storage().persistent().set(&key, &package);
-
-        // Transfer Contract -> Admin
-        let token_client = token::Client::new(&env, &package.token);
-        token_client.transfer(&env.current_contract_address(), &admin, &package.amount);
+        let vested_payout = Self::settle_vested(env, settlement, &mut package);
+        package.status = Self::cancelled_status(&package);
+        Self::save_package(env, &package);
+
+        // Unlock the unclaimed remainder (return to pool)
+        settlement.unlock(&package.token, package.remaining());
+        Self::release_campaign(env, &package, package.remaining(), false);
+        Self::restore_allowance(
+            env,
+            &package.created_by,
+            &package.token,
+            package.remaining(),
+        );
+        if let Some(recipient) = &package.recipient
+            && vested_payout > 0
+        {
+            Self::pay_recipient(env, settlement, &package, vested_payout, recipient);
+        }
 
-        RefundedEvent {
+        RevokedEvent {
             id,
             admin: admin.clone(),
-            amount: package.amount,
+            amount: package.remaining(),
         }
-        .publish(&env);
+        .publish(env);
 
-        Ok(())
+        Ok(package.remaining())
     }
 
-    /// Admin-only package cancellation.
-    /// Requirements: Admin auth, existing package, status must be 'Created'.
-    pub fn cancel_package(env: Env, package_id: u64) -> Result<(), Error> {
-        // 1. Only the admin can cancel (check stored admin and require_auth)
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
-
+    fn cancel_internal(
+        env: &Env,
+        settlement: &mut Settlement,
+        admin: &Address,
+        package_id: u64,
+    ) -> Result<i128, Error> {
         // 2. Package must exist
-        let key = (symbol_short!("pkg"), package_id);
+        let key = DataKey::Package(package_id);
         let mut package: Package = env
             .storage()
             .persistent()
             .get(&key)
             .ok_or(Error::PackageNotFound)?;
 
-        // 3. Package status must be Created (not Claimed, Expired, or already Cancelled)
-        if package.status != PackageStatus::Created {
+        // 3. Package must still be claimable (not Claimed, Expired, or already Cancelled)
+        if !package.is_active() {
             return Err(Error::PackageNotActive);
         }
 
@@ -688,150 +4277,231 @@ impl AidEscrow {
             return Err(Error::PackageExpired);
         }
 
-        // 4. Update status to Cancelled and persist
-        package.status = PackageStatus::Cancelled;
-        env.storage().persistent().set(&key, &package);
-
-        // 5. Unlock funds (Decrement the global locked amount so funds return to the pool)
-        Self::decrement_locked(&env, &package.token, package.amount);
+        // 4. Pay out anything vested, update status to Cancelled and persist
+        let vested_payout = Self::settle_vested(env, settlement, &mut package);
+        package.status = Self::cancelled_status(&package);
+        Self::save_package(env, &package);
+
+        // 5. Unlock the unclaimed remainder (Decrement the global locked amount so funds return to the pool)
+        settlement.unlock(&package.token, package.remaining());
+        Self::release_campaign(env, &package, package.remaining(), false);
+        Self::restore_allowance(
+            env,
+            &package.created_by,
+            &package.token,
+            package.remaining(),
+        );
+        if let Some(recipient) = &package.recipient
+            && vested_payout > 0
+        {
+            Self::pay_recipient(env, settlement, &package, vested_payout, recipient);
+        }
 
         // Reuse RevokedEvent or create a new CancelledEvent if preferred
         RevokedEvent {
             id: package_id,
-            admin,
-            amount: package.amount,
+            admin: admin.clone(),
+            amount: package.remaining(),
         }
-        .publish(&env);
+        .publish(env);
 
-        Ok(())
+        Ok(package.remaining())
     }
 
-    /// Admin-only package expiration extension.
-    /// Requirements: Admin auth, existing package, status must be 'Created', additional_time > 0.
-    /// Behavior: Adds additional_time to the package's expires_at timestamp.
-    /// Cannot extend unbounded packages (expires_at == 0).
-    pub fn extend_expiration(env: Env, package_id: u64, additional_time: u64) -> Result<(), Error> {
-        // 1. Only the admin can extend (check stored admin and require_auth)
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
-        let config = Self::get_config(env.clone());
-
-        // 2. Package must exist
-        let key = (symbol_short!("pkg"), package_id);
+    fn disburse_internal(
+        env: &Env,
+        settlement: &mut Settlement,
+        admin: Address,
+        id: u64,
+    ) -> Result<i128, Error> {
+        let key = DataKey::Package(id);
         let mut package: Package = env
             .storage()
             .persistent()
             .get(&key)
             .ok_or(Error::PackageNotFound)?;
 
-        // 3. Package status must be Created
-        if package.status != PackageStatus::Created {
+        if !package.is_active() {
             return Err(Error::PackageNotActive);
         }
+        // Hash-locked packages have no recipient until claimed with the secret
+        let recipient = package.recipient.clone().ok_or(Error::InvalidState)?;
 
-        // 4. additional_time must be greater than 0
-        if additional_time == 0 {
-            return Err(Error::InvalidAmount);
+        // State Transition
+        let payout = package.available(env.ledger().timestamp());
+        if payout == 0 {
+            return Err(Error::NothingToClaim);
         }
+        package.claimed_amount += payout;
+        package.status = if package.remaining() == 0 {
+            PackageStatus::Claimed
+        } else {
+            PackageStatus::PartiallyClaimed
+        };
+        Self::save_package(env, &package);
 
-        // 5. Package must not be unbounded (expires_at must be > 0)
-        if package.expires_at == 0 {
-            return Err(Error::InvalidState);
-        }
+        // Update Locked
+        settlement.unlock(&package.token, payout);
+        Self::release_campaign(env, &package, payout, true);
 
-        // 6. Package must not already be expired
-        if env.ledger().timestamp() > package.expires_at {
-            return Err(Error::PackageExpired);
+        // Transfer
+        settlement.pay(&package.token, &recipient, payout);
+
+        DisbursedEvent {
+            id,
+            admin: admin.clone(),
+            amount: payout,
         }
+        .publish(env);
 
-        // 7. Calculate new expiration and update
-        let old_expires_at = package.expires_at;
-        let new_expires_at = old_expires_at + additional_time;
-        if config.max_expires_in > 0 {
-            let now = env.ledger().timestamp();
-            if new_expires_at <= now || new_expires_at - now > config.max_expires_in {
+        Ok(payout)
+    }
+
+    fn refund_internal(
+        env: &Env,
+        settlement: &mut Settlement,
+        admin: Address,
+        id: u64,
+    ) -> Result<i128, Error> {
+        let key = DataKey::Package(id);
+        let mut package: Package = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::PackageNotFound)?;
+
+        // Can only refund if Expired or Cancelled.
+        // If Created or PartiallyClaimed, must Revoke first. If Claimed, impossible.
+        // If Refunded, impossible. Only the unclaimed remainder is refunded.
+        if package.is_active() {
+            // Check if actually expired
+            if package.expires_at > 0 && env.ledger().timestamp() > package.expires_at {
+                package.status = PackageStatus::Expired;
+                // If we just expired it, we need to unlock the funds first
+                settlement.unlock(&package.token, package.remaining());
+                Self::release_campaign(env, &package, package.remaining(), false);
+            } else {
                 return Err(Error::InvalidState);
             }
+        } else if package.status == PackageStatus::Claimed
+            || package.status == PackageStatus::Refunded
+        {
+            return Err(Error::InvalidState);
         }
-        package.expires_at = new_expires_at;
-        env.storage().persistent().set(&key, &package);
 
-        // 8. Emit Extended event
-        ExtendedEvent {
-            id: package_id,
+        // If Cancelled, funds were already unlocked in `revoke`.
+        // If Expired (logic above), funds were just unlocked.
+
+        // State Transition
+        package.status = PackageStatus::Refunded;
+        Self::save_package(env, &package);
+
+        // Transfer Contract -> Admin
+        settlement.pay(&package.token, &admin, package.remaining());
+
+        RefundedEvent {
+            id,
             admin: admin.clone(),
-            old_expires_at,
-            new_expires_at,
+            amount: package.remaining(),
         }
-        .publish(&env);
+        .publish(env);
 
-        Ok(())
+        Ok(package.remaining())
     }
 
-    /// Admin-only function to withdraw surplus (unallocated) funds from the contract.
-    /// Requirements: Admin auth, valid amount, sufficient surplus available.
-    /// Behavior: Transfers amount of token from contract to the specified address.
-    pub fn withdraw_surplus(
-        env: Env,
+    fn withdraw_surplus_internal(
+        env: &Env,
         to: Address,
         amount: i128,
         token: Address,
     ) -> Result<(), Error> {
-        // 1. Only the admin can withdraw surplus
-        let admin = Self::get_admin(env.clone())?;
-        admin.require_auth();
-
-        // 2. Validate amount
+        // 1. Validate amount
         if amount <= 0 {
             return Err(Error::InvalidAmount);
         }
 
-        // 3. Get contract's current balance for the token
-        let token_client = token::Client::new(&env, &token);
+        // 2. Get contract's current balance for the token
+        let token_client = token::Client::new(env, &token);
         let contract_balance = token_client.balance(&env.current_contract_address());
 
-        // 4. Get total locked amount for the token
+        // 3. Get total locked amount for the token
         let locked_map: Map<Address, i128> = env
             .storage()
             .instance()
-            .get(&KEY_TOTAL_LOCKED)
-            .unwrap_or(Map::new(&env));
+            .get(&DataKey::TotalLocked)
+            .unwrap_or(Map::new(env));
         let total_locked = locked_map.get(token.clone()).unwrap_or(0);
 
-        // 5. Calculate available surplus and validate
+        // 4. Calculate available surplus and validate
         let available_surplus = contract_balance - total_locked;
         if amount > available_surplus {
             return Err(Error::InsufficientSurplus);
         }
 
-        // 6. Transfer funds from contract to recipient
+        // 5. Transfer funds from contract to recipient
         token_client.transfer(&env.current_contract_address(), &to, &amount);
 
-        // 7. Emit event
+        // 6. Emit event
         SurplusWithdrawnEvent {
             to: to.clone(),
             token: token.clone(),
             amount,
         }
-        .publish(&env);
+        .publish(env);
 
         Ok(())
     }
 
-    // --- Helpers ---
+    /// Locks `amount` of `token` if the unlocked balance covers it.
+    fn lock_funds(env: &Env, token: &Address, amount: i128) -> Result<(), Error> {
+        let token_client = token::Client::new(env, token);
+        let contract_balance = token_client.balance(&env.current_contract_address());
 
-    fn check_paused(env: &Env) -> Result<(), Error> {
-        if env.storage().instance().get(&KEY_PAUSED).unwrap_or(false) {
-            return Err(Error::ContractPaused);
+        let mut locked_map: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalLocked)
+            .unwrap_or(Map::new(env));
+        let current_locked = locked_map.get(token.clone()).unwrap_or(0);
+
+        // Ensure we don't over-promise funds
+        if contract_balance < current_locked + amount {
+            return Err(Error::InsufficientFunds);
         }
+
+        locked_map.set(token.clone(), current_locked + amount);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalLocked, &locked_map);
         Ok(())
     }
 
+    fn save_distribution(env: &Env, distribution: &Distribution) {
+        let key = DataKey::Distribution(distribution.id);
+        env.storage().persistent().set(&key, distribution);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PACKAGE_TTL_EXTEND, PACKAGE_TTL_EXTEND);
+    }
+
+    fn store_voucher_budget(env: &Env, issuer: &Address, token: &Address, budget: i128) {
+        env.storage().persistent().set(
+            &DataKey::VoucherBudget(issuer.clone(), token.clone()),
+            &budget,
+        );
+        VoucherBudgetChangedEvent {
+            issuer: issuer.clone(),
+            token: token.clone(),
+            budget,
+        }
+        .publish(env);
+    }
+
     fn decrement_locked(env: &Env, token: &Address, amount: i128) {
         let mut locked_map: Map<Address, i128> = env
             .storage()
             .instance()
-            .get(&KEY_TOTAL_LOCKED)
+            .get(&DataKey::TotalLocked)
             .unwrap_or(Map::new(env));
 
         let current = locked_map.get(token.clone()).unwrap_or(0);
@@ -842,23 +4512,108 @@ impl AidEscrow {
         };
 
         locked_map.set(token.clone(), new_locked);
-        env.storage().instance().set(&KEY_TOTAL_LOCKED, &locked_map);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalLocked, &locked_map);
     }
 
-    fn require_admin_or_distributor(env: &Env, operator: &Address) -> Result<(), Error> {
-        operator.require_auth();
+    /// The admin creates without an allowance unless the timelock is
+    /// enabled, which would otherwise let it lock funds to itself and claim
+    /// them without notice.
+    fn is_allowance_exempt(env: &Env, operator: &Address) -> Result<bool, Error> {
+        Ok(
+            *operator == Self::get_admin(env.clone())?
+                && Self::get_timelock_delay(env.clone()) == 0,
+        )
+    }
 
-        let admin = Self::get_admin(env.clone())?;
-        if *operator == admin {
+    /// Deducts `amount` from the operator's allowance unless it is exempt.
+    fn consume_allowance(
+        env: &Env,
+        operator: &Address,
+        token: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        if Self::is_allowance_exempt(env, operator)? {
             return Ok(());
         }
 
-        let distributors: Map<Address, bool> = env
+        let mut allowance =
+            Self::get_distributor_allowance(env.clone(), operator.clone(), token.clone())
+                .ok_or(Error::AllowanceExceeded)?;
+        if allowance.remaining < amount {
+            return Err(Error::AllowanceExceeded);
+        }
+
+        allowance.remaining -= amount;
+        env.storage().persistent().set(
+            &DataKey::Allowance(operator.clone(), token.clone()),
+            &allowance,
+        );
+        Ok(())
+    }
+
+    /// Gives `amount` back to the allowance of the distributor that locked
+    /// it, when a package it created is revoked or cancelled or its voucher
+    /// budget is released. The admin is skipped, as it may have locked funds
+    /// while exempt.
+    fn restore_allowance(env: &Env, operator: &Address, token: &Address, amount: i128) {
+        if Self::get_admin(env.clone()).ok().as_ref() == Some(operator) {
+            return;
+        }
+        let key = DataKey::Allowance(operator.clone(), token.clone());
+        if let Some(mut allowance) = env
             .storage()
-            .instance()
-            .get(&KEY_DISTRIBUTORS)
-            .unwrap_or(Map::new(env));
-        if distributors.get(operator.clone()).unwrap_or(false) {
+            .persistent()
+            .get::<_, DistributorAllowance>(&key)
+        {
+            allowance.remaining += amount;
+            env.storage().persistent().set(&key, &allowance);
+        }
+    }
+
+    /// Returns `amount` of a campaign package to its campaign. When `spent` is
+    /// true the amount was paid out and counts against the budget for good.
+    fn release_campaign(env: &Env, package: &Package, amount: i128, spent: bool) {
+        let Some(campaign_id) = package.campaign_id else {
+            return;
+        };
+        let key = DataKey::Campaign(campaign_id);
+        if let Some(mut campaign) = env.storage().persistent().get::<_, Campaign>(&key) {
+            campaign.locked = if campaign.locked > amount {
+                campaign.locked - amount
+            } else {
+                0
+            };
+            if spent {
+                campaign.spent += amount;
+            }
+            env.storage().persistent().set(&key, &campaign);
+        }
+    }
+
+    fn require_admin_or_role(env: &Env, caller: &Address, role: Role) -> Result<(), Error> {
+        caller.require_auth();
+
+        let admin = Self::get_admin(env.clone())?;
+        if *caller == admin || Self::has_role(env.clone(), role, caller.clone()) {
+            Ok(())
+        } else {
+            Err(Error::NotAuthorized)
+        }
+    }
+
+    /// Like `require_admin_or_role`, but an admin caller of a council-gated
+    /// operation must go through the council once one is configured.
+    fn require_role_or_sole_admin(env: &Env, caller: &Address, role: Role) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        if *caller == admin {
+            Self::require_sole_admin(env)?;
+            return Ok(());
+        }
+
+        caller.require_auth();
+        if Self::has_role(env.clone(), role, caller.clone()) {
             Ok(())
         } else {
             Err(Error::NotAuthorized)
@@ -866,7 +4621,7 @@ impl AidEscrow {
     }
 
     pub fn get_package(env: Env, id: u64) -> Result<Package, Error> {
-        let key = (symbol_short!("pkg"), id);
+        let key = DataKey::Package(id);
         env.storage()
             .persistent()
             .get(&key)
@@ -880,52 +4635,217 @@ impl AidEscrow {
         Ok(pkg.status)
     }
 
+    /// Lists packages in creation order. Scans up to `limit` index positions
+    /// (capped at `MAX_PAGE_SIZE`, 0 meaning the cap) starting at `cursor` (0
+    /// for the first page) and returns those matching `filter`, so a page may
+    /// hold fewer than `limit` packages while `next_cursor` is still set.
+    pub fn list_packages(env: Env, cursor: u64, limit: u32, filter: PackageFilter) -> PackagePage {
+        let count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PackageIndexCount)
+            .unwrap_or(0);
+        let end = count.min(cursor.saturating_add(Self::page_size(limit, MAX_PAGE_SIZE)));
+
+        let mut packages = Vec::new(&env);
+        for idx in cursor..end {
+            let Some(id) = env
+                .storage()
+                .persistent()
+                .get::<_, u64>(&DataKey::PackageIndex(idx))
+            else {
+                continue;
+            };
+            if let Some(package) = env
+                .storage()
+                .persistent()
+                .get::<_, Package>(&DataKey::Package(id))
+                && Self::matches_filter(&package, &filter)
+            {
+                packages.push_back(package);
+            }
+        }
+
+        PackagePage {
+            packages,
+            next_cursor: if end < count { Some(end) } else { None },
+        }
+    }
+
+    /// Lists a recipient's packages in creation order, `limit` (capped at
+    /// `MAX_PAGE_SIZE`, 0 meaning the cap) at a time starting at `cursor` (0
+    /// for the first page).
+    pub fn get_packages_for_recipient(
+        env: Env,
+        recipient: Address,
+        cursor: u64,
+        limit: u32,
+    ) -> PackagePage {
+        let count: u64 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RecipientPackageCount(recipient.clone()))
+            .unwrap_or(0);
+        let end = count.min(cursor.saturating_add(Self::page_size(limit, MAX_PAGE_SIZE)));
+
+        let mut packages = Vec::new(&env);
+        for pos in cursor..end {
+            if let Some(id) = env
+                .storage()
+                .persistent()
+                .get::<_, u64>(&DataKey::RecipientPackage(recipient.clone(), pos))
+                && let Some(package) = env
+                    .storage()
+                    .persistent()
+                    .get::<_, Package>(&DataKey::Package(id))
+            {
+                packages.push_back(package);
+            }
+        }
+
+        PackagePage {
+            packages,
+            next_cursor: if end < count { Some(end) } else { None },
+        }
+    }
+
+    /// Total a recipient can claim right now in `token`: the sum of their
+    /// `Created` packages that have not expired.
+    pub fn get_claimable_total(env: Env, recipient: Address, token: Address) -> i128 {
+        let count: u64 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RecipientPackageCount(recipient.clone()))
+            .unwrap_or(0);
+        let now = env.ledger().timestamp();
+
+        let mut total: i128 = 0;
+        for pos in 0..count {
+            if let Some(id) = env
+                .storage()
+                .persistent()
+                .get::<_, u64>(&DataKey::RecipientPackage(recipient.clone(), pos))
+                && let Some(package) = env
+                    .storage()
+                    .persistent()
+                    .get::<_, Package>(&DataKey::Package(id))
+                && package.token == token
+                && package.is_active()
+                && (package.expires_at == 0 || now <= package.expires_at)
+            {
+                total += package.available(now);
+            }
+        }
+        total
+    }
+
+    fn matches_filter(package: &Package, filter: &PackageFilter) -> bool {
+        let in_range = |value: u64, from: Option<u64>, to: Option<u64>| {
+            from.is_none_or(|from| value >= from) && to.is_none_or(|to| value <= to)
+        };
+
+        (filter.statuses.is_empty() || filter.statuses.contains(package.status))
+            && filter
+                .token
+                .as_ref()
+                .is_none_or(|token| package.token == *token)
+            && filter
+                .recipient
+                .as_ref()
+                .is_none_or(|recipient| package.recipient.as_ref() == Some(recipient))
+            && in_range(package.created_at, filter.created_from, filter.created_to)
+            && in_range(package.expires_at, filter.expires_from, filter.expires_to)
+    }
+
     // --- Analytics ---
 
-    /// Returns aggregate statistics for a given token.
+    /// Returns aggregate statistics for a given token:
+    /// - `total_committed`: unclaimed amounts of packages still in `Created` or
+    ///    `PartiallyClaimed` status,
+    /// - `total_claimed`: amounts paid out, including partial claims,
+    /// - `total_expired_cancelled`: unclaimed amounts of packages in `Expired`,
+    ///    `Cancelled`, or `Refunded` status,
     ///
-    /// Iterates across all created packages and computes:
-    /// - `total_committed`: sum of amounts for packages still in `Created` status,
-    /// - `total_claimed`: sum of amounts for packages in `Claimed` status,
-    /// - `total_expired_cancelled`: sum of amounts for packages in `Expired`,
-    ///    `Cancelled`, or `Refunded` status.
+    /// along with the number of packages in each group.
     ///
-    /// This is a read-only view intended for dashboards and analytics.
+    /// Counters are maintained on every state transition. On deployments
+    /// migrated from an earlier schema they are partial until
+    /// `rebuild_aggregates` completes.
     pub fn get_aggregates(env: Env, token: Address) -> Aggregates {
-        let count: u64 = env.storage().instance().get(&KEY_PKG_IDX).unwrap_or(0);
-
-        let mut total_committed: i128 = 0;
-        let mut total_claimed: i128 = 0;
-        let mut total_expired_cancelled: i128 = 0;
-
-        for i in 0..count {
-            let idx_key = (symbol_short!("pidx"), i);
-            if let Some(pkg_id) = env.storage().persistent().get::<_, u64>(&idx_key) {
-                let pkg_key = (symbol_short!("pkg"), pkg_id);
-                if let Some(package) = env.storage().persistent().get::<_, Package>(&pkg_key)
-                    && package.token == token
-                {
-                    match package.status {
-                        PackageStatus::Created => {
-                            total_committed += package.amount;
-                        }
-                        PackageStatus::Claimed => {
-                            total_claimed += package.amount;
-                        }
-                        PackageStatus::Expired
-                        | PackageStatus::Cancelled
-                        | PackageStatus::Refunded => {
-                            total_expired_cancelled += package.amount;
-                        }
-                    }
-                }
+        env.storage()
+            .persistent()
+            .get(&DataKey::Aggregates(token))
+            .unwrap_or(Aggregates {
+                total_committed: 0,
+                total_claimed: 0,
+                total_expired_cancelled: 0,
+                committed_count: 0,
+                claimed_count: 0,
+                expired_cancelled_count: 0,
+            })
+    }
+
+    /// Folds packages that existed before aggregates were tracked into the
+    /// counters, `limit` (capped at `MAX_REBUILD_BATCH`, 0 meaning the cap)
+    /// index positions at a time. `cursor` must be the value returned by the previous call (0 for
+    /// the first). Returns the next cursor, or `None` once the rebuild is
+    /// complete. Callable by the admin or an `Auditor` (keeper).
+    pub fn rebuild_aggregates(
+        env: Env,
+        caller: Address,
+        cursor: u64,
+        limit: u32,
+    ) -> Result<Option<u64>, Error> {
+        Self::require_admin_or_role(&env, &caller, Role::Auditor)?;
+        // Packages the migration has not reached yet cannot be counted
+        if env.storage().instance().has(&DataKey::Migration) {
+            return Err(Error::InvalidState);
+        }
+
+        let mut rebuild: AggregatesRebuild = env
+            .storage()
+            .instance()
+            .get(&DataKey::AggregatesRebuild)
+            .ok_or(Error::InvalidState)?;
+        // Each position must be counted exactly once
+        if cursor != rebuild.next {
+            return Err(Error::InvalidState);
+        }
+
+        let end = rebuild
+            .end
+            .min(cursor.saturating_add(Self::page_size(limit, MAX_REBUILD_BATCH)));
+        let mut batch: Map<Address, Aggregates> = Map::new(&env);
+        for idx in cursor..end {
+            if let Some(id) = env
+                .storage()
+                .persistent()
+                .get::<_, u64>(&DataKey::PackageIndex(idx))
+                && let Some(package) = env
+                    .storage()
+                    .persistent()
+                    .get::<_, Package>(&DataKey::Package(id))
+            {
+                let mut aggregates = batch
+                    .get(package.token.clone())
+                    .unwrap_or_else(|| Self::get_aggregates(env.clone(), package.token.clone()));
+                Self::tally(&mut aggregates, &package, true);
+                batch.set(package.token.clone(), aggregates);
             }
         }
+        for (token, aggregates) in batch.iter() {
+            Self::store_aggregates(&env, &DataKey::Aggregates(token), &aggregates);
+        }
 
-        Aggregates {
-            total_committed,
-            total_claimed,
-            total_expired_cancelled,
+        if end >= rebuild.end {
+            env.storage().instance().remove(&DataKey::AggregatesRebuild);
+            Ok(None)
+        } else {
+            rebuild.next = end;
+            env.storage()
+                .instance()
+                .set(&DataKey::AggregatesRebuild, &rebuild);
+            Ok(Some(end))
         }
     }
 }
@@ -0,0 +1,179 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{CouncilAction, Error, PackageStatus};
+use common::setup_funded;
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Ledger},
+};
+
+#[test]
+fn test_propose_and_accept_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, admin) = setup_funded(&env);
+    let new_admin = Address::generate(&env);
+
+    client.propose_admin(&new_admin, &0);
+    let pending = client.get_pending_admin().unwrap();
+    assert_eq!(pending.new_admin, new_admin);
+
+    // Admin does not change until accepted
+    assert_eq!(client.get_admin(), admin);
+
+    client.accept_admin();
+
+    // Acceptance was authorized by the new admin
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, new_admin);
+
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+}
+
+#[test]
+fn test_accept_without_pending_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, _admin) = setup_funded(&env);
+
+    assert_eq!(client.try_accept_admin(), Err(Ok(Error::NoPendingAdmin)));
+    assert_eq!(
+        client.try_cancel_admin_transfer(),
+        Err(Ok(Error::NoPendingAdmin))
+    );
+}
+
+#[test]
+fn test_cancel_admin_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, admin) = setup_funded(&env);
+    let new_admin = Address::generate(&env);
+
+    client.propose_admin(&new_admin, &0);
+    client.cancel_admin_transfer();
+
+    assert_eq!(client.get_pending_admin(), None);
+    assert_eq!(client.try_accept_admin(), Err(Ok(Error::NoPendingAdmin)));
+    assert_eq!(client.get_admin(), admin);
+}
+
+#[test]
+fn test_admin_transfer_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, admin) = setup_funded(&env);
+    let new_admin = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    // Expiry in the past is rejected
+    env.ledger().with_mut(|li| li.timestamp = now + 10);
+    assert_eq!(
+        client.try_propose_admin(&new_admin, &(now + 5)),
+        Err(Ok(Error::InvalidState))
+    );
+
+    client.propose_admin(&new_admin, &(now + 100));
+    env.ledger().with_mut(|li| li.timestamp = now + 101);
+
+    assert_eq!(
+        client.try_accept_admin(),
+        Err(Ok(Error::AdminTransferExpired))
+    );
+    assert_eq!(client.get_admin(), admin);
+}
+
+#[test]
+fn test_rotation_with_live_packages() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let new_admin = Address::generate(&env);
+    let recipient1 = Address::generate(&env);
+    let recipient2 = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    client.create_package(
+        &admin,
+        &1,
+        &recipient1,
+        &1_000,
+        &token_client.address,
+        &(now + 86400),
+    );
+    client.create_package(
+        &admin,
+        &2,
+        &recipient2,
+        &2_000,
+        &token_client.address,
+        &(now + 86400),
+    );
+
+    client.propose_admin(&new_admin, &(now + 3600));
+    client.accept_admin();
+
+    // Recipients can still claim packages created under the old admin
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient1), 1_000);
+
+    // The new admin now authorizes privileged actions
+    client.revoke(&2);
+    let auths = env.auths();
+    assert_eq!(auths[0].0, new_admin);
+    assert_eq!(client.get_package(&2).status, PackageStatus::Cancelled);
+
+    // Refunds go to the new admin
+    client.refund(&new_admin, &2);
+    assert_eq!(token_client.balance(&new_admin), 2_000);
+    assert_eq!(token_client.balance(&admin), 0);
+
+    // The old admin is no longer recognized as an operator
+    let result = client.try_create_package(
+        &admin,
+        &3,
+        &recipient1,
+        &100,
+        &token_client.address,
+        &(now + 86400),
+    );
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+}
+
+#[test]
+fn test_admin_transfer_requires_council() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, admin) = setup_funded(&env);
+    let new_admin = Address::generate(&env);
+    let signer = Address::generate(&env);
+    client.set_council(&Vec::from_array(&env, [signer.clone()]), &1);
+
+    // The admin key alone can no longer hand over control
+    assert_eq!(
+        client.try_propose_admin(&new_admin, &0),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_cancel_admin_transfer(),
+        Err(Ok(Error::CouncilRequired))
+    );
+
+    let proposal_id = client.propose(&signer, &CouncilAction::ProposeAdmin(new_admin.clone(), 0));
+    client.execute(&proposal_id);
+    assert_eq!(client.get_pending_admin().unwrap().new_admin, new_admin);
+    assert_eq!(client.get_admin(), admin);
+
+    client.accept_admin();
+    assert_eq!(client.get_admin(), new_admin);
+}
@@ -55,6 +55,9 @@ fn test_aggregates_no_packages() {
             total_committed: 0,
             total_claimed: 0,
             total_expired_cancelled: 0,
+            committed_count: 0,
+            claimed_count: 0,
+            expired_cancelled_count: 0,
         }
     );
 }
@@ -117,7 +120,7 @@ fn test_aggregates_mixed_statuses() {
     client.create_package(&admin, &4, &r4, &750, &token_client.address, &short_expiry);
     // Advance past short_expiry to expire
     env.ledger().set_timestamp(short_expiry + 1);
-    client.refund(&4);
+    client.refund(&admin, &4);
 
     let agg = client.get_aggregates(&token_client.address);
     assert_eq!(agg.total_committed, 1000); // pkg 1 (Created)
@@ -344,9 +347,37 @@ fn test_aggregates_revoke_then_refund() {
     assert_eq!(agg2.total_expired_cancelled, 4000);
 
     // After refund (Refunded — still in expired/cancelled bucket)
-    client.refund(&1);
+    client.refund(&admin, &1);
     let agg3 = client.get_aggregates(&token_client.address);
     assert_eq!(agg3.total_committed, 0);
     assert_eq!(agg3.total_claimed, 0);
     assert_eq!(agg3.total_expired_cancelled, 4000);
 }
+
+#[test]
+fn test_aggregates_package_counts() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, _contract_id) = setup_funded(&env, 10_000);
+
+    let r = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    client.create_package(&admin, &1, &r, &1000, &token_client.address, &(now + 100));
+    client.create_package(&admin, &2, &r, &2000, &token_client.address, &(now + 100));
+    client.create_package(&admin, &3, &r, &3000, &token_client.address, &0);
+    client.claim(&2);
+
+    // Refunding an expired package moves it out of committed
+    env.ledger().set_timestamp(now + 101);
+    client.refund(&admin, &1);
+
+    let agg = client.get_aggregates(&token_client.address);
+    assert_eq!(agg.total_committed, 3000);
+    assert_eq!(agg.committed_count, 1);
+    assert_eq!(agg.total_claimed, 2000);
+    assert_eq!(agg.claimed_count, 1);
+    assert_eq!(agg.total_expired_cancelled, 1000);
+    assert_eq!(agg.expired_cancelled_count, 1);
+}
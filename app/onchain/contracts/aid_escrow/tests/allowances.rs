@@ -0,0 +1,293 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{AidEscrow, AidEscrowClient, Error};
+use common::setup_token;
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Ledger},
+    token::TokenClient,
+};
+
+/// Helper: funded contract with one registered distributor.
+/// Returns the client, token client, admin and distributor.
+fn setup_distributor(
+    env: &Env,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Address,
+) {
+    let admin = Address::generate(env);
+    let distributor = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &10_000);
+    client.fund(&token_client.address, &admin, &10_000);
+    client.add_distributor(&distributor);
+
+    (client, token_client, admin, distributor)
+}
+
+#[test]
+fn test_distributor_without_allowance_cannot_create() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin, distributor) = setup_distributor(&env);
+    let recipient = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 86400;
+
+    let result = client.try_create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &100,
+        &token_client.address,
+        &expires_at,
+    );
+    assert_eq!(result, Err(Ok(Error::AllowanceExceeded)));
+    assert_eq!(
+        client.get_distributor_allowance(&distributor, &token_client.address),
+        None
+    );
+
+    // The admin is not subject to allowances
+    client.create_package(
+        &admin,
+        &1,
+        &recipient,
+        &100,
+        &token_client.address,
+        &expires_at,
+    );
+}
+
+#[test]
+fn test_allowance_consumed_and_enforced() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _admin, distributor) = setup_distributor(&env);
+    let recipient = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 86400;
+
+    client.set_distributor_allowance(&distributor, &token_client.address, &1_000, &0, &0);
+
+    client.create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &600,
+        &token_client.address,
+        &expires_at,
+    );
+    let allowance = client
+        .get_distributor_allowance(&distributor, &token_client.address)
+        .unwrap();
+    assert_eq!(allowance.remaining, 400);
+
+    // Pool still holds 9,400 available, but the allowance caps the distributor
+    let result = client.try_create_package(
+        &distributor,
+        &2,
+        &recipient,
+        &500,
+        &token_client.address,
+        &expires_at,
+    );
+    assert_eq!(result, Err(Ok(Error::AllowanceExceeded)));
+}
+
+#[test]
+fn test_batch_consumes_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _admin, distributor) = setup_distributor(&env);
+
+    client.set_distributor_allowance(&distributor, &token_client.address, &1_000, &0, &0);
+
+    let mut recipients = Vec::new(&env);
+    recipients.push_back(Address::generate(&env));
+    recipients.push_back(Address::generate(&env));
+    let mut amounts = Vec::new(&env);
+    amounts.push_back(300_i128);
+    amounts.push_back(400_i128);
+
+    client.batch_create_packages(
+        &distributor,
+        &recipients,
+        &amounts,
+        &token_client.address,
+        &86400,
+    );
+    let allowance = client
+        .get_distributor_allowance(&distributor, &token_client.address)
+        .unwrap();
+    assert_eq!(allowance.remaining, 300);
+
+    // A batch over the remaining allowance fails as a whole
+    let result = client.try_batch_create_packages(
+        &distributor,
+        &recipients,
+        &amounts,
+        &token_client.address,
+        &86400,
+    );
+    assert_eq!(result, Err(Ok(Error::AllowanceExceeded)));
+}
+
+#[test]
+fn test_revoke_and_cancel_restore_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _admin, distributor) = setup_distributor(&env);
+    let recipient = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 86400;
+
+    client.set_distributor_allowance(&distributor, &token_client.address, &1_000, &0, &0);
+
+    client.create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &600,
+        &token_client.address,
+        &expires_at,
+    );
+    client.create_package(
+        &distributor,
+        &2,
+        &recipient,
+        &300,
+        &token_client.address,
+        &expires_at,
+    );
+    assert_eq!(
+        client
+            .get_distributor_allowance(&distributor, &token_client.address)
+            .unwrap()
+            .remaining,
+        100
+    );
+
+    client.revoke(&1);
+    assert_eq!(
+        client
+            .get_distributor_allowance(&distributor, &token_client.address)
+            .unwrap()
+            .remaining,
+        700
+    );
+
+    client.cancel_package(&2);
+    assert_eq!(
+        client
+            .get_distributor_allowance(&distributor, &token_client.address)
+            .unwrap()
+            .remaining,
+        1_000
+    );
+}
+
+#[test]
+fn test_claim_does_not_restore_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _admin, distributor) = setup_distributor(&env);
+    let recipient = Address::generate(&env);
+
+    client.set_distributor_allowance(&distributor, &token_client.address, &1_000, &0, &0);
+    client.create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &600,
+        &token_client.address,
+        &(env.ledger().timestamp() + 86400),
+    );
+    client.claim(&1);
+
+    assert_eq!(
+        client
+            .get_distributor_allowance(&distributor, &token_client.address)
+            .unwrap()
+            .remaining,
+        400
+    );
+}
+
+#[test]
+fn test_allowance_refills_each_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _admin, distributor) = setup_distributor(&env);
+    let recipient = Address::generate(&env);
+    let start = env.ledger().timestamp();
+    let week = 7 * 86400;
+
+    client.set_distributor_allowance(&distributor, &token_client.address, &500, &500, &week);
+
+    client.create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &500,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(
+        client
+            .get_distributor_allowance(&distributor, &token_client.address)
+            .unwrap()
+            .remaining,
+        0
+    );
+
+    // Two and a half weeks later the allowance has been refilled once
+    env.ledger().set_timestamp(start + week * 2 + week / 2);
+    let allowance = client
+        .get_distributor_allowance(&distributor, &token_client.address)
+        .unwrap();
+    assert_eq!(allowance.remaining, 500);
+    assert_eq!(allowance.period_start, start + week * 2);
+
+    client.create_package(
+        &distributor,
+        &2,
+        &recipient,
+        &200,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(
+        client
+            .get_distributor_allowance(&distributor, &token_client.address)
+            .unwrap()
+            .remaining,
+        300
+    );
+}
+
+#[test]
+fn test_set_allowance_invalid_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _admin, distributor) = setup_distributor(&env);
+
+    let result =
+        client.try_set_distributor_allowance(&distributor, &token_client.address, &-1, &0, &0);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+}
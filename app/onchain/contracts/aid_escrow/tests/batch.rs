@@ -1,6 +1,6 @@
 #![cfg(test)]
 
-use aid_escrow::{AidEscrow, AidEscrowClient, Error, PackageStatus};
+use aid_escrow::{AidEscrow, AidEscrowClient, BatchCreateResult, Error, PackageStatus, Role};
 use soroban_sdk::{
     Address, Env, Vec,
     testutils::Address as _,
@@ -63,17 +63,17 @@ fn test_batch_create_packages_success() {
 
     // Verify each package
     let pkg0 = client.get_package(&0);
-    assert_eq!(pkg0.recipient, recipient1);
+    assert_eq!(pkg0.recipient, Some(recipient1));
     assert_eq!(pkg0.amount, 1000);
     assert_eq!(pkg0.status, PackageStatus::Created);
 
     let pkg1 = client.get_package(&1);
-    assert_eq!(pkg1.recipient, recipient2);
+    assert_eq!(pkg1.recipient, Some(recipient2));
     assert_eq!(pkg1.amount, 2000);
     assert_eq!(pkg1.status, PackageStatus::Created);
 
     let pkg2 = client.get_package(&2);
-    assert_eq!(pkg2.recipient, recipient3);
+    assert_eq!(pkg2.recipient, Some(recipient3));
     assert_eq!(pkg2.amount, 3000);
     assert_eq!(pkg2.status, PackageStatus::Created);
 
@@ -263,11 +263,133 @@ fn test_batch_then_individual_no_id_collision() {
     );
 
     let pkg = client.get_package(&manual_id);
-    assert_eq!(pkg.recipient, recipient3);
+    assert_eq!(pkg.recipient, Some(recipient3));
 
     // Verify batch packages are still intact
     let pkg0 = client.get_package(&0);
-    assert_eq!(pkg0.recipient, recipient1);
+    assert_eq!(pkg0.recipient, Some(recipient1));
     let pkg1 = client.get_package(&1);
-    assert_eq!(pkg1.recipient, recipient2);
+    assert_eq!(pkg1.recipient, Some(recipient2));
+}
+
+#[test]
+fn test_batch_create_best_effort_skips_invalid_rows() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &1_000);
+    client.fund(&token_client.address, &admin, &1_000);
+
+    let recipients = Vec::from_array(
+        &env,
+        [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ],
+    );
+    // Second row is invalid, third exceeds what is left in the pool
+    let amounts = Vec::from_array(&env, [400, 0, 700, 600]);
+
+    // The default mode still rejects the whole batch
+    assert_eq!(
+        client.try_batch_create_packages(
+            &admin,
+            &recipients,
+            &amounts,
+            &token_client.address,
+            &86400
+        ),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    let results = client.batch_create_best_effort(
+        &admin,
+        &recipients,
+        &amounts,
+        &token_client.address,
+        &86400,
+    );
+    assert_eq!(
+        results,
+        Vec::from_array(
+            &env,
+            [
+                BatchCreateResult::Created(0),
+                BatchCreateResult::Failed(Error::InvalidAmount as u32),
+                BatchCreateResult::Failed(Error::InsufficientFunds as u32),
+                BatchCreateResult::Created(1),
+            ]
+        )
+    );
+
+    assert_eq!(
+        client.get_package(&0).recipient,
+        Some(recipients.get(0).unwrap())
+    );
+    assert_eq!(
+        client.get_package(&1).recipient,
+        Some(recipients.get(3).unwrap())
+    );
+    assert_eq!(client.get_package(&1).amount, 600);
+    assert_eq!(client.try_get_package(&2), Err(Ok(Error::PackageNotFound)));
+}
+
+#[test]
+fn test_batch_create_best_effort_respects_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let distributor = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &10_000);
+    client.fund(&token_client.address, &admin, &10_000);
+    client.grant_role(&Role::Distributor, &distributor);
+    client.set_distributor_allowance(&distributor, &token_client.address, &500, &0, &0);
+
+    let recipients = Vec::from_array(
+        &env,
+        [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ],
+    );
+    let amounts = Vec::from_array(&env, [300, 300, 200]);
+
+    let results = client.batch_create_best_effort(
+        &distributor,
+        &recipients,
+        &amounts,
+        &token_client.address,
+        &86400,
+    );
+    assert_eq!(
+        results.get(1).unwrap(),
+        BatchCreateResult::Failed(Error::AllowanceExceeded as u32)
+    );
+    assert_eq!(results.get(2).unwrap(), BatchCreateResult::Created(1));
+    assert_eq!(
+        client
+            .get_distributor_allowance(&distributor, &token_client.address)
+            .unwrap()
+            .remaining,
+        0
+    );
 }
@@ -0,0 +1,181 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{BatchOutcome, Error, PackageStatus};
+use common::setup_funded;
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Events, Ledger},
+    token::TokenClient,
+};
+
+/// Helper: number of events the token contract emitted in the last call.
+fn token_events(env: &Env, token: &TokenClient) -> usize {
+    env.events()
+        .all()
+        .iter()
+        .filter(|(contract, _, _)| *contract == token.address)
+        .count()
+}
+
+fn failed(error: Error) -> BatchOutcome {
+    BatchOutcome::Failed(error as u32)
+}
+
+#[test]
+fn test_batch_claim_pays_once_per_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+
+    for id in 1..=3u64 {
+        client.create_package(&admin, &id, &recipient, &100, &token_client.address, &0);
+    }
+    client.claim(&3);
+
+    let results = client.batch_claim(&Vec::from_array(&env, [1, 2, 3, 9]));
+    assert_eq!(token_events(&env, &token_client), 1);
+
+    assert_eq!(results.len(), 4);
+    assert_eq!(results.get(0).unwrap().outcome, BatchOutcome::Ok(100));
+    assert_eq!(results.get(1).unwrap().outcome, BatchOutcome::Ok(100));
+    assert_eq!(
+        results.get(2).unwrap().outcome,
+        failed(Error::PackageNotActive)
+    );
+    assert_eq!(results.get(3).unwrap().id, 9);
+    assert_eq!(
+        results.get(3).unwrap().outcome,
+        failed(Error::PackageNotFound)
+    );
+
+    assert_eq!(token_client.balance(&recipient), 300);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Claimed);
+    assert_eq!(client.get_package(&2).status, PackageStatus::Claimed);
+}
+
+#[test]
+fn test_batch_disburse() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    client.create_package(&admin, &1, &alice, &100, &token_client.address, &0);
+    client.create_package(&admin, &2, &bob, &200, &token_client.address, &0);
+    client.create_package(&admin, &3, &alice, &300, &token_client.address, &0);
+
+    let results = client.batch_disburse(&Vec::from_array(&env, [1, 2, 3]));
+    assert_eq!(token_events(&env, &token_client), 2);
+    assert!(
+        results
+            .iter()
+            .all(|result| matches!(result.outcome, BatchOutcome::Ok(_)))
+    );
+
+    assert_eq!(token_client.balance(&alice), 400);
+    assert_eq!(token_client.balance(&bob), 200);
+
+    // Everything paid out is unlocked: the rest of the pool is surplus
+    client.withdraw_surplus(&admin, &9_400, &token_client.address);
+}
+
+#[test]
+fn test_batch_revoke_and_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    client.create_package(&admin, &1, &recipient, &100, &token_client.address, &0);
+    client.create_package(&admin, &2, &recipient, &200, &token_client.address, &0);
+    client.create_package(
+        &admin,
+        &3,
+        &recipient,
+        &300,
+        &token_client.address,
+        &expires_at,
+    );
+
+    let results = client.batch_revoke(&Vec::from_array(&env, [1, 2]));
+    assert_eq!(results.get(0).unwrap().outcome, BatchOutcome::Ok(100));
+    assert_eq!(results.get(1).unwrap().outcome, BatchOutcome::Ok(200));
+    assert_eq!(client.get_package(&2).status, PackageStatus::Cancelled);
+
+    // Package 3 cannot be refunded until it expires
+    let results = client.batch_refund(&admin, &Vec::from_array(&env, [1, 2, 3]));
+    assert_eq!(token_events(&env, &token_client), 1);
+    assert_eq!(results.get(2).unwrap().outcome, failed(Error::InvalidState));
+    assert_eq!(token_client.balance(&admin), 300);
+
+    env.ledger().set_timestamp(expires_at + 1);
+    let results = client.batch_refund(&admin, &Vec::from_array(&env, [3, 1]));
+    assert_eq!(results.get(0).unwrap().outcome, BatchOutcome::Ok(300));
+    assert_eq!(results.get(1).unwrap().outcome, failed(Error::InvalidState));
+    assert_eq!(token_client.balance(&admin), 600);
+    assert_eq!(client.get_package(&3).status, PackageStatus::Refunded);
+
+    // Nothing remains locked
+    client.withdraw_surplus(&admin, &9_400, &token_client.address);
+}
+
+#[test]
+fn test_batch_cancel_packages() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    client.create_package(&admin, &1, &recipient, &100, &token_client.address, &0);
+    client.create_package(
+        &admin,
+        &2,
+        &recipient,
+        &200,
+        &token_client.address,
+        &expires_at,
+    );
+
+    env.ledger().set_timestamp(expires_at + 1);
+    let results = client.batch_cancel_packages(&Vec::from_array(&env, [1, 2]));
+    assert_eq!(results.get(0).unwrap().outcome, BatchOutcome::Ok(100));
+    assert_eq!(
+        results.get(1).unwrap().outcome,
+        failed(Error::PackageExpired)
+    );
+    assert_eq!(client.get_package(&1).status, PackageStatus::Cancelled);
+    assert_eq!(client.get_package(&2).status, PackageStatus::Created);
+}
+
+#[test]
+fn test_batch_admin_operations_require_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, _admin) = setup_funded(&env);
+    let outsider = Address::generate(&env);
+    let ids = Vec::from_array(&env, [1u64]);
+
+    assert_eq!(
+        client.try_batch_refund(&outsider, &ids),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+    client.set_council(&signers, &1);
+    assert_eq!(
+        client.try_batch_disburse(&ids),
+        Err(Ok(Error::CouncilRequired))
+    );
+}
@@ -0,0 +1,223 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{CampaignStatus, Error, PackageStatus};
+use common::setup_funded;
+use soroban_sdk::{
+    Address, Env, String,
+    testutils::{Address as _, Ledger},
+};
+
+#[test]
+fn test_create_and_close_campaign() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _admin) = setup_funded(&env);
+
+    let now = env.ledger().timestamp();
+    let id = client.create_campaign(
+        &String::from_str(&env, "Flood response"),
+        &token_client.address,
+        &5_000,
+        &now,
+        &(now + 86400),
+    );
+    assert_eq!(id, 0);
+
+    let campaign = client.get_campaign(&id);
+    assert_eq!(campaign.budget, 5_000);
+    assert_eq!(campaign.status, CampaignStatus::Active);
+    assert_eq!(campaign.locked, 0);
+    assert_eq!(campaign.spent, 0);
+
+    client.close_campaign(&id);
+    assert_eq!(client.get_campaign(&id).status, CampaignStatus::Closed);
+
+    // Closing twice is rejected
+    let result = client.try_close_campaign(&id);
+    assert_eq!(result, Err(Ok(Error::CampaignNotActive)));
+
+    // Unknown campaign
+    let result = client.try_get_campaign(&42);
+    assert_eq!(result, Err(Ok(Error::CampaignNotFound)));
+}
+
+#[test]
+fn test_campaign_invalid_params() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _admin) = setup_funded(&env);
+    let name = String::from_str(&env, "Drought");
+
+    let result = client.try_create_campaign(&name, &token_client.address, &0, &0, &0);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+    let result = client.try_create_campaign(&name, &token_client.address, &100, &500, &400);
+    assert_eq!(result, Err(Ok(Error::InvalidState)));
+}
+
+#[test]
+fn test_campaign_package_accounting() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient1 = Address::generate(&env);
+    let recipient2 = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    let campaign_id = client.create_campaign(
+        &String::from_str(&env, "Flood response"),
+        &token_client.address,
+        &3_000,
+        &now,
+        &0,
+    );
+
+    client.create_campaign_package(
+        &admin,
+        &campaign_id,
+        &1,
+        &recipient1,
+        &1_000,
+        &(now + 86400),
+    );
+    client.create_campaign_package(
+        &admin,
+        &campaign_id,
+        &2,
+        &recipient2,
+        &2_000,
+        &(now + 86400),
+    );
+
+    let pkg = client.get_package(&1);
+    assert_eq!(pkg.campaign_id, Some(campaign_id));
+    assert_eq!(pkg.token, token_client.address);
+
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.locked, 3_000);
+    assert_eq!(campaign.spent, 0);
+
+    // Claim moves funds from locked to spent
+    client.claim(&1);
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.locked, 2_000);
+    assert_eq!(campaign.spent, 1_000);
+
+    // Revoke returns funds to the campaign budget
+    client.revoke(&2);
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.locked, 0);
+    assert_eq!(campaign.spent, 1_000);
+    assert_eq!(client.get_package(&2).status, PackageStatus::Cancelled);
+
+    // Released budget can be reused
+    client.create_campaign_package(
+        &admin,
+        &campaign_id,
+        &3,
+        &recipient2,
+        &2_000,
+        &(now + 86400),
+    );
+    assert_eq!(client.get_campaign(&campaign_id).locked, 2_000);
+}
+
+#[test]
+fn test_campaign_budget_exceeded() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    let campaign_id = client.create_campaign(
+        &String::from_str(&env, "Winter kits"),
+        &token_client.address,
+        &1_500,
+        &now,
+        &0,
+    );
+
+    client.create_campaign_package(&admin, &campaign_id, &1, &recipient, &1_000, &(now + 86400));
+
+    // Pool has plenty of funds, but the campaign budget does not
+    let result = client.try_create_campaign_package(
+        &admin,
+        &campaign_id,
+        &2,
+        &recipient,
+        &600,
+        &(now + 86400),
+    );
+    assert_eq!(result, Err(Ok(Error::CampaignBudgetExceeded)));
+
+    // Package outside a campaign is unaffected
+    client.create_package(
+        &admin,
+        &3,
+        &recipient,
+        &600,
+        &token_client.address,
+        &(now + 86400),
+    );
+}
+
+#[test]
+fn test_campaign_not_active() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    let campaign_id = client.create_campaign(
+        &String::from_str(&env, "Cash transfers"),
+        &token_client.address,
+        &5_000,
+        &(now + 100),
+        &(now + 1_000),
+    );
+
+    // Not started yet
+    let result = client.try_create_campaign_package(
+        &admin,
+        &campaign_id,
+        &1,
+        &recipient,
+        &100,
+        &(now + 86400),
+    );
+    assert_eq!(result, Err(Ok(Error::CampaignNotActive)));
+
+    // Past the end date
+    env.ledger().with_mut(|li| li.timestamp = now + 2_000);
+    let result = client.try_create_campaign_package(
+        &admin,
+        &campaign_id,
+        &1,
+        &recipient,
+        &100,
+        &(now + 86400),
+    );
+    assert_eq!(result, Err(Ok(Error::CampaignNotActive)));
+
+    // Closed campaign
+    let open_id = client.create_campaign(
+        &String::from_str(&env, "Open-ended"),
+        &token_client.address,
+        &5_000,
+        &0,
+        &0,
+    );
+    client.close_campaign(&open_id);
+    let result =
+        client.try_create_campaign_package(&admin, &open_id, &1, &recipient, &100, &(now + 86400));
+    assert_eq!(result, Err(Ok(Error::CampaignNotActive)));
+}
@@ -0,0 +1,68 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{Error, PackageStatus};
+use common::setup_funded;
+use soroban_sdk::{
+    Address, Env, Map, Symbol, TryFromVal, Val,
+    testutils::{Address as _, Events},
+};
+
+#[test]
+fn test_claim_to_destination() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let anchor = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &500, &token_client.address, &0);
+    client.claim_to(&1, &anchor);
+    let auths = env.auths();
+    let events = env.events().all();
+
+    // Authorized by the recipient, paid to the destination
+    assert_eq!(auths[0].0, recipient);
+    assert_eq!(token_client.balance(&anchor), 500);
+    assert_eq!(token_client.balance(&recipient), 0);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Claimed);
+
+    // The claim event names both
+    let (_contract, _topics, data) = events
+        .iter()
+        .find(|(contract, _, _)| *contract == client.address)
+        .unwrap();
+    let data: Map<Symbol, Val> = Map::try_from_val(&env, &data).unwrap();
+    let field = |name: &str| {
+        Address::try_from_val(&env, &data.get(Symbol::new(&env, name)).unwrap()).unwrap()
+    };
+    assert_eq!(field("recipient"), recipient);
+    assert_eq!(field("destination"), anchor);
+}
+
+#[test]
+fn test_claim_to_follows_claim_rules() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let anchor = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &500, &token_client.address, &0);
+    client.claim_partial(&1, &200);
+    client.claim_to(&1, &anchor);
+    assert_eq!(token_client.balance(&recipient), 200);
+    assert_eq!(token_client.balance(&anchor), 300);
+
+    assert_eq!(
+        client.try_claim_to(&1, &anchor),
+        Err(Ok(Error::PackageNotActive))
+    );
+    assert_eq!(
+        client.try_claim_to(&2, &anchor),
+        Err(Ok(Error::PackageNotFound))
+    );
+}
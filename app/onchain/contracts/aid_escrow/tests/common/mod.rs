@@ -0,0 +1,45 @@
+//! Setup shared by the integration tests. Each test crate uses only some of
+//! these helpers.
+#![allow(dead_code)]
+
+use aid_escrow::{AidEscrow, AidEscrowClient};
+use soroban_sdk::{
+    Address, Env,
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+};
+
+pub fn setup_token(
+    env: &Env,
+    admin: &Address,
+) -> (TokenClient<'static>, StellarAssetClient<'static>) {
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = TokenClient::new(env, &token_contract.address());
+    let token_admin_client = StellarAssetClient::new(env, &token_contract.address());
+    (token_client, token_admin_client)
+}
+
+/// Helper: set up a contract funded with 10_000 and return the client, token
+/// client and admin.
+pub fn setup_funded(env: &Env) -> (AidEscrowClient<'static>, TokenClient<'static>, Address) {
+    setup_funded_with(env, 10_000)
+}
+
+/// Like `setup_funded`, funding the contract with `fund_amount`.
+pub fn setup_funded_with(
+    env: &Env,
+    fund_amount: i128,
+) -> (AidEscrowClient<'static>, TokenClient<'static>, Address) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &fund_amount);
+    client.fund(&token_client.address, &admin, &fund_amount);
+
+    (client, token_client, admin)
+}
@@ -0,0 +1,487 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{
+    AidEscrow, AidEscrowClient, Config, CouncilAction, Error, OperationStatus, PackageStatus,
+    ProposalStatus, RecurringSchedule, Role, TimelockOp, VestingSchedule,
+};
+use common::setup_token;
+use soroban_sdk::{Address, BytesN, Env, String, Vec, testutils::Address as _, token::TokenClient};
+
+/// Helper: funded contract with a 2-of-3 council and package 1 of 1_000 for
+/// `recipient`, created before the council. Returns client, token, admin and
+/// signers.
+fn setup_council(
+    env: &Env,
+    recipient: &Address,
+) -> (
+    AidEscrowClient<'static>,
+    TokenClient<'static>,
+    Address,
+    Vec<Address>,
+) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &10_000);
+    client.fund(&token_client.address, &admin, &10_000);
+    client.create_package(
+        &admin,
+        &1,
+        recipient,
+        &1_000,
+        &token_client.address,
+        &(env.ledger().timestamp() + 86400),
+    );
+
+    let mut signers = Vec::new(env);
+    signers.push_back(Address::generate(env));
+    signers.push_back(Address::generate(env));
+    signers.push_back(Address::generate(env));
+    client.set_council(&signers, &2);
+
+    (client, token_client, admin, signers)
+}
+
+#[test]
+fn test_direct_privileged_calls_require_council() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let recipient = Address::generate(&env);
+    let (client, token_client, admin, _signers) = setup_council(&env, &recipient);
+
+    assert_eq!(client.try_disburse(&1), Err(Ok(Error::CouncilRequired)));
+    assert_eq!(
+        client.try_refund(&admin, &1),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(client.try_pause(&admin), Err(Ok(Error::CouncilRequired)));
+    assert_eq!(
+        client.try_withdraw_surplus(&admin, &100, &token_client.address),
+        Err(Ok(Error::CouncilRequired))
+    );
+
+    // Council can only be configured once
+    let result = client.try_set_council(&Vec::new(&env), &1);
+    assert_eq!(result, Err(Ok(Error::AlreadyInitialized)));
+}
+
+#[test]
+fn test_admin_key_alone_cannot_move_funds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let recipient = Address::generate(&env);
+    let (client, token_client, admin, signers) = setup_council(&env, &recipient);
+    let token = token_client.address.clone();
+    let thief = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 86400;
+    let hash = BytesN::from_array(&env, &[1u8; 32]);
+
+    // Every way for the admin to point pool funds at an address of its choosing
+    assert_eq!(
+        client.try_create_package(&admin, &99, &thief, &9_000, &token, &0),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client
+            .validate_package(&admin, &99, &9_000, &token, &0)
+            .errors,
+        Vec::from_array(&env, [Error::CouncilRequired as u32])
+    );
+    let vesting = VestingSchedule {
+        start: 0,
+        cliff: 0,
+        end: 1,
+    };
+    assert_eq!(
+        client.try_create_vesting_package(&admin, &99, &thief, &9_000, &token, &vesting, &0),
+        Err(Ok(Error::CouncilRequired))
+    );
+    let recurring = RecurringSchedule {
+        start: 0,
+        period: 1,
+        periods: 1,
+    };
+    assert_eq!(
+        client.try_create_recurring_package(&admin, &99, &thief, &9_000, &token, &recurring, &0),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_create_hashlocked_package(&admin, &99, &9_000, &token, &hash, &expires_at),
+        Err(Ok(Error::CouncilRequired))
+    );
+    let campaign = client.create_campaign(&String::from_str(&env, "flood"), &token, &9_000, &0, &0);
+    assert_eq!(
+        client.try_create_campaign_package(&admin, &campaign, &99, &thief, &9_000, &0),
+        Err(Ok(Error::CouncilRequired))
+    );
+    let thieves = Vec::from_array(&env, [thief.clone()]);
+    let amounts = Vec::from_array(&env, [9_000]);
+    assert_eq!(
+        client.try_batch_create_packages(&admin, &thieves, &amounts, &token, &0),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_batch_create_best_effort(&admin, &thieves, &amounts, &token, &0),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_set_voucher_signer(&admin, &hash),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_fund_voucher_budget(&admin, &token, &9_000),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_create_distribution(&admin, &token, &hash, &9_000, &expires_at),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_grant_role(&Role::Distributor, &thief),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_set_distributor_allowance(&thief, &token, &9_000, &0, &0),
+        Err(Ok(Error::CouncilRequired))
+    );
+    let reason = String::from_str(&env, "typo");
+    assert_eq!(
+        client.try_reassign_recipient(&1, &thief, &reason, &false),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(token_client.balance(&thief), 0);
+
+    // Nor can it wipe out packages, strip roles or hold up the council
+    let ids = Vec::from_array(&env, [1]);
+    assert_eq!(client.try_revoke(&1), Err(Ok(Error::CouncilRequired)));
+    assert_eq!(
+        client.try_cancel_package(&1),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_batch_revoke(&ids),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_batch_cancel_packages(&ids),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_revoke_role(&Role::Distributor, &thief),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_set_timelock_delay(&3600),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_queue_operation(&TimelockOp::RemoveDistributor(thief.clone())),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_queue_operation(&TimelockOp::SetDelay(3600)),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(client.get_package(&1).status, PackageStatus::Created);
+
+    // The council grants the same powers instead
+    let s0 = signers.get(0).unwrap();
+    let s1 = signers.get(1).unwrap();
+    let pass = |action: CouncilAction| {
+        let proposal_id = client.propose(&s0, &action);
+        client.approve(&s1, &proposal_id);
+        client.execute(&proposal_id);
+    };
+    let officer = Address::generate(&env);
+    pass(CouncilAction::GrantRole(Role::Distributor, officer.clone()));
+    pass(CouncilAction::SetAllowance(
+        officer.clone(),
+        token.clone(),
+        500,
+        0,
+        0,
+    ));
+    pass(CouncilAction::ReassignRecipient(
+        1,
+        officer.clone(),
+        reason,
+        false,
+    ));
+
+    client.create_package(&officer, &2, &recipient, &500, &token, &0);
+    assert_eq!(
+        client.try_create_package(&officer, &3, &recipient, &1, &token, &0),
+        Err(Ok(Error::AllowanceExceeded))
+    );
+    assert_eq!(client.get_package(&1).recipient, Some(officer.clone()));
+
+    pass(CouncilAction::Cancel(2));
+    assert_eq!(client.get_package(&2).status, PackageStatus::Cancelled);
+    pass(CouncilAction::BatchRevoke(ids));
+    assert_eq!(client.get_package(&1).status, PackageStatus::Cancelled);
+
+    // Once the council enables the timelock, only it can cancel what it queued
+    pass(CouncilAction::SetTimelockDelay(3600));
+    pass(CouncilAction::WithdrawSurplus(
+        s0.clone(),
+        100,
+        token.clone(),
+    ));
+    assert_eq!(
+        client.try_cancel_operation(&0),
+        Err(Ok(Error::CouncilRequired))
+    );
+    pass(CouncilAction::CancelOperation(0));
+    assert_eq!(client.get_operation(&0).status, OperationStatus::Cancelled);
+
+    // Removing a distributor waits out the delay
+    pass(CouncilAction::RevokeRole(
+        Role::Distributor,
+        officer.clone(),
+    ));
+    assert!(client.has_role(&Role::Distributor, &officer));
+    assert_eq!(
+        client.get_operation(&1).op,
+        TimelockOp::RemoveDistributor(officer)
+    );
+}
+
+#[test]
+fn test_proposal_flow_disburse() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let recipient = Address::generate(&env);
+    let (client, token_client, _admin, signers) = setup_council(&env, &recipient);
+    let s0 = signers.get(0).unwrap();
+    let s1 = signers.get(1).unwrap();
+
+    let proposal_id = client.propose(&s0, &CouncilAction::Disburse(1));
+
+    // One approval is below the 2-of-3 threshold
+    assert_eq!(
+        client.try_execute(&proposal_id),
+        Err(Ok(Error::ThresholdNotMet))
+    );
+
+    // Double approval is rejected
+    assert_eq!(
+        client.try_approve(&s0, &proposal_id),
+        Err(Ok(Error::AlreadyApproved))
+    );
+
+    client.approve(&s1, &proposal_id);
+    client.execute(&proposal_id);
+
+    assert_eq!(client.get_package(&1).status, PackageStatus::Claimed);
+    assert_eq!(token_client.balance(&recipient), 1_000);
+    assert_eq!(
+        client.get_proposal(&proposal_id).status,
+        ProposalStatus::Executed
+    );
+
+    // Executed proposals cannot run twice
+    assert_eq!(
+        client.try_execute(&proposal_id),
+        Err(Ok(Error::ProposalNotPending))
+    );
+}
+
+#[test]
+fn test_non_signer_cannot_propose_or_approve() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, _admin, signers) = setup_council(&env, &Address::generate(&env));
+    let outsider = Address::generate(&env);
+
+    assert_eq!(
+        client.try_propose(&outsider, &CouncilAction::Pause),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    let proposal_id = client.propose(&signers.get(0).unwrap(), &CouncilAction::Pause);
+    assert_eq!(
+        client.try_approve(&outsider, &proposal_id),
+        Err(Ok(Error::NotAuthorized))
+    );
+}
+
+#[test]
+fn test_cancel_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, _admin, signers) = setup_council(&env, &Address::generate(&env));
+    let s0 = signers.get(0).unwrap();
+    let s2 = signers.get(2).unwrap();
+
+    let proposal_id = client.propose(&s0, &CouncilAction::Pause);
+    client.cancel_proposal(&s2, &proposal_id);
+    assert_eq!(
+        client.get_proposal(&proposal_id).status,
+        ProposalStatus::Cancelled
+    );
+
+    assert_eq!(
+        client.try_approve(&s2, &proposal_id),
+        Err(Ok(Error::ProposalNotPending))
+    );
+    assert!(!client.is_paused());
+}
+
+#[test]
+fn test_council_set_config_and_pause() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, _admin, signers) = setup_council(&env, &Address::generate(&env));
+    let s0 = signers.get(0).unwrap();
+    let s1 = signers.get(1).unwrap();
+
+    let config = Config {
+        min_amount: 50,
+        max_expires_in: 3600,
+        allowed_tokens: Vec::new(&env),
+    };
+    let proposal_id = client.propose(&s0, &CouncilAction::SetConfig(config.clone()));
+    client.approve(&s1, &proposal_id);
+    client.execute(&proposal_id);
+    assert_eq!(client.get_config(), config);
+
+    let proposal_id = client.propose(&s1, &CouncilAction::Pause);
+    client.approve(&s0, &proposal_id);
+    client.execute(&proposal_id);
+    assert!(client.is_paused());
+}
+
+#[test]
+fn test_signer_management_requires_threshold() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, _admin, signers) = setup_council(&env, &Address::generate(&env));
+    let s0 = signers.get(0).unwrap();
+    let s1 = signers.get(1).unwrap();
+    let s2 = signers.get(2).unwrap();
+    let newcomer = Address::generate(&env);
+
+    // Add a fourth signer
+    let proposal_id = client.propose(&s0, &CouncilAction::AddSigner(newcomer.clone()));
+    client.approve(&s1, &proposal_id);
+    client.execute(&proposal_id);
+    assert_eq!(client.get_council().signers.len(), 4);
+
+    // Raise threshold to 3
+    let proposal_id = client.propose(&s0, &CouncilAction::SetThreshold(3));
+    client.approve(&newcomer, &proposal_id);
+    client.execute(&proposal_id);
+    assert_eq!(client.get_council().threshold, 3);
+
+    // Remove s2; approvals from s2 no longer count afterwards
+    let pending = client.propose(&s2, &CouncilAction::Pause);
+    let proposal_id = client.propose(&s0, &CouncilAction::RemoveSigner(s2.clone()));
+    client.approve(&s1, &proposal_id);
+    client.approve(&newcomer, &proposal_id);
+    client.execute(&proposal_id);
+    assert!(!client.get_council().signers.contains(s2.clone()));
+
+    client.approve(&s0, &pending);
+    client.approve(&s1, &pending);
+    assert_eq!(
+        client.try_execute(&pending),
+        Err(Ok(Error::ThresholdNotMet))
+    );
+
+    // Threshold cannot exceed signer count
+    let proposal_id = client.propose(&s0, &CouncilAction::SetThreshold(5));
+    client.approve(&s1, &proposal_id);
+    client.approve(&newcomer, &proposal_id);
+    assert_eq!(
+        client.try_execute(&proposal_id),
+        Err(Ok(Error::InvalidThreshold))
+    );
+}
+
+#[test]
+fn test_set_council_invalid_threshold() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+    signers.push_back(Address::generate(&env));
+
+    assert_eq!(
+        client.try_set_council(&signers, &0),
+        Err(Ok(Error::InvalidThreshold))
+    );
+    assert_eq!(
+        client.try_set_council(&signers, &3),
+        Err(Ok(Error::InvalidThreshold))
+    );
+    assert_eq!(client.try_get_council(), Err(Ok(Error::CouncilNotSet)));
+}
+
+#[test]
+fn test_council_batch_disburse_and_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let signer = Address::generate(&env);
+    let (token_client, token_admin_client) = setup_token(&env, &Address::generate(&env));
+    let token = token_client.address.clone();
+    let client = AidEscrowClient::new(&env, &env.register(AidEscrow, ()));
+    client.init(&admin);
+    token_admin_client.mint(&admin, &10_000);
+    client.fund(&token, &admin, &10_000);
+    for id in 1..=4 {
+        client.create_package(&admin, &id, &recipient, &1_000, &token, &0);
+    }
+    client.set_council(&Vec::from_array(&env, [signer.clone()]), &1);
+
+    let ids = Vec::from_array(&env, [1, 2, 9]);
+    assert_eq!(
+        client.try_batch_disburse(&ids),
+        Err(Ok(Error::CouncilRequired))
+    );
+    assert_eq!(
+        client.try_batch_refund(&admin, &ids),
+        Err(Ok(Error::CouncilRequired))
+    );
+
+    // A failing item does not stop the rest of the batch
+    let proposal_id = client.propose(&signer, &CouncilAction::BatchDisburse(ids));
+    client.execute(&proposal_id);
+    assert_eq!(client.get_package(&2).status, PackageStatus::Claimed);
+    assert_eq!(token_client.balance(&recipient), 2_000);
+
+    let proposal_id = client.propose(
+        &signer,
+        &CouncilAction::BatchRevoke(Vec::from_array(&env, [3, 4])),
+    );
+    client.execute(&proposal_id);
+    let proposal_id = client.propose(
+        &signer,
+        &CouncilAction::BatchRefund(Vec::from_array(&env, [1, 3, 4])),
+    );
+    client.execute(&proposal_id);
+    assert_eq!(client.get_package(&4).status, PackageStatus::Refunded);
+    assert_eq!(token_client.balance(&admin), 2_000);
+}
@@ -0,0 +1,117 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{Error, PackageStatus};
+use common::setup_funded;
+use soroban_sdk::{Address, Env, testutils::Address as _};
+
+#[test]
+fn test_delegate_claims_for_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let officer = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &500, &token_client.address, &0);
+    client.create_package(&admin, &2, &recipient, &300, &token_client.address, &0);
+
+    // Without a delegation the officer cannot claim
+    assert_eq!(
+        client.try_claim_on_behalf(&1),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    client.set_claim_delegate(&recipient, &None, &officer, &false);
+    assert_eq!(
+        client
+            .get_claim_delegate(&recipient, &None)
+            .unwrap()
+            .delegate,
+        officer
+    );
+
+    client.claim_on_behalf(&1);
+    let auths = env.auths();
+    assert_eq!(auths[0].0, officer);
+
+    // Funds still go to the recipient
+    assert_eq!(token_client.balance(&recipient), 500);
+    assert_eq!(token_client.balance(&officer), 0);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Claimed);
+
+    // Revoking the delegation stops further claims
+    client.revoke_claim_delegate(&recipient, &None);
+    assert_eq!(
+        client.try_claim_on_behalf(&2),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_revoke_claim_delegate(&recipient, &None),
+        Err(Ok(Error::InvalidState))
+    );
+}
+
+#[test]
+fn test_per_package_delegate_paid_directly() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let officer = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &500, &token_client.address, &0);
+    client.create_package(&admin, &2, &recipient, &300, &token_client.address, &0);
+
+    client.set_claim_delegate(&recipient, &Some(1), &officer, &true);
+
+    // The delegation covers only the package it names
+    assert_eq!(
+        client.try_claim_on_behalf(&2),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    client.claim_on_behalf(&1);
+    assert_eq!(token_client.balance(&officer), 500);
+    assert_eq!(token_client.balance(&recipient), 0);
+
+    // A per-package delegate takes precedence over a recipient-wide one
+    client.set_claim_delegate(&recipient, &None, &other, &false);
+    client.set_claim_delegate(&recipient, &Some(2), &officer, &false);
+    client.claim_on_behalf(&2);
+    let auths = env.auths();
+    assert_eq!(auths[0].0, officer);
+    assert_eq!(token_client.balance(&recipient), 300);
+}
+
+#[test]
+fn test_only_recipient_can_delegate_package() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &500, &token_client.address, &0);
+
+    assert_eq!(
+        client.try_set_claim_delegate(&stranger, &Some(1), &stranger, &true),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_set_claim_delegate(&recipient, &Some(9), &stranger, &true),
+        Err(Ok(Error::PackageNotFound))
+    );
+
+    // A stranger's recipient-wide delegation does not reach other people's packages
+    client.set_claim_delegate(&stranger, &None, &stranger, &true);
+    assert_eq!(
+        client.try_claim_on_behalf(&1),
+        Err(Ok(Error::NotAuthorized))
+    );
+}
@@ -0,0 +1,167 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{Error, PackageStatus};
+use common::setup_funded;
+use soroban_sdk::{
+    Address, Bytes, BytesN, Env,
+    testutils::{Address as _, Ledger},
+    xdr::ToXdr,
+};
+
+/// Helper: a claim secret and its sha256.
+fn secret(env: &Env, code: &str) -> (Bytes, BytesN<32>) {
+    let secret = Bytes::from_slice(env, code.as_bytes());
+    let hash = env.crypto().sha256(&secret).to_bytes();
+    (secret, hash)
+}
+
+/// Helper: the commitment to claiming with `secret` to `to`.
+fn commitment(env: &Env, secret: &Bytes, to: &Address) -> BytesN<32> {
+    env.crypto()
+        .sha256(&(secret.clone(), to.clone()).to_xdr(env))
+        .to_bytes()
+}
+
+fn next_ledger(env: &Env) {
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 1);
+}
+
+#[test]
+fn test_claim_with_secret() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let (code, hash) = secret(&env, "7Q4K-MZ2P-91XD");
+    let to = Address::generate(&env);
+
+    client.create_hashlocked_package(&admin, &1, &500, &token_client.address, &hash, &0);
+    let package = client.get_package(&1);
+    assert_eq!(package.recipient, None);
+    assert_eq!(client.claimable_now(&1), 500);
+
+    client.commit_secret_claim(&1, &commitment(&env, &code, &to));
+    next_ledger(&env);
+    client.claim_with_secret(&1, &code, &to);
+
+    let package = client.get_package(&1);
+    assert_eq!(package.status, PackageStatus::Claimed);
+    assert_eq!(package.recipient, Some(to.clone()));
+    assert_eq!(token_client.balance(&to), 500);
+
+    // The claimant is indexed as the recipient from then on
+    let page = client.get_packages_for_recipient(&to, &0, &10);
+    assert_eq!(page.packages.len(), 1);
+
+    // The secret cannot be used twice
+    assert_eq!(
+        client.try_claim_with_secret(&1, &code, &to),
+        Err(Ok(Error::PackageNotActive))
+    );
+}
+
+#[test]
+fn test_wrong_secret_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let (_code, hash) = secret(&env, "7Q4K-MZ2P-91XD");
+    let (wrong, _) = secret(&env, "7Q4K-MZ2P-91XE");
+    let to = Address::generate(&env);
+
+    client.create_hashlocked_package(&admin, &1, &500, &token_client.address, &hash, &0);
+    client.commit_secret_claim(&1, &commitment(&env, &wrong, &to));
+    next_ledger(&env);
+    assert_eq!(
+        client.try_claim_with_secret(&1, &wrong, &to),
+        Err(Ok(Error::InvalidSecret))
+    );
+    assert_eq!(client.get_package(&1).status, PackageStatus::Created);
+}
+
+#[test]
+fn test_claim_cannot_be_redirected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let (code, hash) = secret(&env, "7Q4K-MZ2P-91XD");
+    let to = Address::generate(&env);
+    let thief = Address::generate(&env);
+
+    client.create_hashlocked_package(&admin, &1, &500, &token_client.address, &hash, &0);
+
+    // Revealing the secret without a prior commitment pays nobody
+    assert_eq!(
+        client.try_claim_with_secret(&1, &code, &to),
+        Err(Ok(Error::ClaimNotCommitted))
+    );
+
+    client.commit_secret_claim(&1, &commitment(&env, &code, &to));
+    next_ledger(&env);
+
+    // Someone who learns the secret from a pending claim can only commit in
+    // the same ledger, which is too late to claim before it
+    client.commit_secret_claim(&1, &commitment(&env, &code, &thief));
+    assert_eq!(
+        client.try_claim_with_secret(&1, &code, &thief),
+        Err(Ok(Error::ClaimNotCommitted))
+    );
+    assert_eq!(client.get_package(&1).status, PackageStatus::Created);
+
+    client.claim_with_secret(&1, &code, &to);
+    assert_eq!(token_client.balance(&to), 500);
+    assert_eq!(token_client.balance(&thief), 0);
+}
+
+#[test]
+fn test_hashlocked_package_needs_secret() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let (code, hash) = secret(&env, "7Q4K-MZ2P-91XD");
+    let recipient = Address::generate(&env);
+
+    client.create_hashlocked_package(&admin, &1, &500, &token_client.address, &hash, &0);
+    assert_eq!(client.try_claim(&1), Err(Ok(Error::InvalidState)));
+    assert_eq!(client.try_disburse(&1), Err(Ok(Error::InvalidState)));
+
+    // Ordinary packages cannot be claimed with a secret
+    client.create_package(&admin, &2, &recipient, &100, &token_client.address, &0);
+    assert_eq!(
+        client.try_commit_secret_claim(&2, &commitment(&env, &code, &recipient)),
+        Err(Ok(Error::InvalidState))
+    );
+    assert_eq!(
+        client.try_claim_with_secret(&2, &code, &recipient),
+        Err(Ok(Error::InvalidState))
+    );
+}
+
+#[test]
+fn test_hashlocked_package_expires() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let (code, hash) = secret(&env, "7Q4K-MZ2P-91XD");
+    let to = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    client.create_hashlocked_package(&admin, &1, &500, &token_client.address, &hash, &expires_at);
+    client.commit_secret_claim(&1, &commitment(&env, &code, &to));
+    next_ledger(&env);
+
+    env.ledger().set_timestamp(expires_at + 1);
+    assert_eq!(
+        client.try_claim_with_secret(&1, &code, &to),
+        Err(Ok(Error::PackageExpired))
+    );
+    client.refund(&admin, &1);
+    assert_eq!(token_client.balance(&admin), 500);
+}
@@ -55,7 +55,7 @@ fn test_integration_flow() {
 
     // Verify package details
     let package = client.get_package(&pkg_id);
-    assert_eq!(package.recipient, recipient);
+    assert_eq!(package.recipient, Some(recipient.clone()));
     assert_eq!(package.amount, 1000);
     assert_eq!(package.token, token_client.address);
     assert_eq!(package.status, PackageStatus::Created);
@@ -121,8 +121,8 @@ fn test_multiple_packages() {
     let p1 = client.get_package(&id1);
     let p2 = client.get_package(&id2);
 
-    assert_eq!(p1.recipient, recipient1);
-    assert_eq!(p2.recipient, recipient2);
+    assert_eq!(p1.recipient, Some(recipient1));
+    assert_eq!(p2.recipient, Some(recipient2));
     assert_eq!(p1.amount, 500);
     assert_eq!(p2.amount, 1000);
 
@@ -197,7 +197,7 @@ fn test_set_get_config() {
         max_expires_in: 3600,
         allowed_tokens,
     };
-    client.set_config(&config);
+    client.set_config(&admin, &config);
 
     let stored = client.get_config();
     assert_eq!(stored, config);
@@ -225,11 +225,14 @@ fn test_config_constraints_on_create_package() {
 
     let mut allowed_tokens = Vec::new(&env);
     allowed_tokens.push_back(allowed_token_client.address.clone());
-    client.set_config(&Config {
-        min_amount: 100,
-        max_expires_in: 1000,
-        allowed_tokens,
-    });
+    client.set_config(
+        &admin,
+        &Config {
+            min_amount: 100,
+            max_expires_in: 1000,
+            allowed_tokens,
+        },
+    );
 
     let now = env.ledger().timestamp();
     let too_small = client.try_create_package(
@@ -279,11 +282,14 @@ fn test_config_constraints_on_extend_expiration() {
     token_admin_client.mint(&admin, &10_000);
     client.fund(&token_client.address, &admin, &5000);
 
-    client.set_config(&Config {
-        min_amount: 1,
-        max_expires_in: 1000,
-        allowed_tokens: Vec::new(&env),
-    });
+    client.set_config(
+        &admin,
+        &Config {
+            min_amount: 1,
+            max_expires_in: 1000,
+            allowed_tokens: Vec::new(&env),
+        },
+    );
 
     let now = env.ledger().timestamp();
     let pkg_id = 1;
@@ -0,0 +1,153 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{PackageFilter, PackageStatus};
+use common::setup_funded_with;
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Ledger},
+};
+
+fn no_filter(env: &Env) -> PackageFilter {
+    PackageFilter {
+        statuses: Vec::new(env),
+        token: None,
+        recipient: None,
+        created_from: None,
+        created_to: None,
+        expires_from: None,
+        expires_to: None,
+    }
+}
+
+#[test]
+fn test_list_packages_pagination() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded_with(&env, 100_000);
+    let recipient = Address::generate(&env);
+
+    for id in 0..5u64 {
+        client.create_package(&admin, &id, &recipient, &100, &token_client.address, &0);
+    }
+
+    let page = client.list_packages(&0, &2, &no_filter(&env));
+    assert_eq!(page.packages.len(), 2);
+    assert_eq!(page.packages.get(0).unwrap().id, 0);
+    assert_eq!(page.next_cursor, Some(2));
+
+    let page = client.list_packages(&2, &2, &no_filter(&env));
+    assert_eq!(page.packages.get(0).unwrap().id, 2);
+    assert_eq!(page.next_cursor, Some(4));
+
+    let page = client.list_packages(&4, &2, &no_filter(&env));
+    assert_eq!(page.packages.len(), 1);
+    assert_eq!(page.next_cursor, None);
+
+    // Past the end
+    let page = client.list_packages(&10, &2, &no_filter(&env));
+    assert_eq!(page.packages.len(), 0);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_list_packages_limit_is_capped() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded_with(&env, 100_000);
+    let recipient = Address::generate(&env);
+
+    for id in 0..60u64 {
+        client.create_package(&admin, &id, &recipient, &10, &token_client.address, &0);
+    }
+
+    let page = client.list_packages(&0, &1_000, &no_filter(&env));
+    assert_eq!(page.packages.len(), 50);
+    assert_eq!(page.next_cursor, Some(50));
+
+    // A zero limit still advances instead of returning the same cursor
+    let page = client.list_packages(&0, &0, &no_filter(&env));
+    assert_eq!(page.packages.len(), 50);
+    assert_eq!(page.next_cursor, Some(50));
+    let page = client.get_packages_for_recipient(&recipient, &50, &0);
+    assert_eq!(page.packages.len(), 10);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_list_packages_filters() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded_with(&env, 100_000);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let start = env.ledger().timestamp();
+
+    client.create_package(
+        &admin,
+        &1,
+        &alice,
+        &100,
+        &token_client.address,
+        &(start + 500),
+    );
+    env.ledger().set_timestamp(start + 100);
+    client.create_package(
+        &admin,
+        &2,
+        &bob,
+        &100,
+        &token_client.address,
+        &(start + 1_000),
+    );
+    client.create_package(&admin, &3, &alice, &100, &token_client.address, &0);
+    client.claim(&3);
+
+    let by_recipient = PackageFilter {
+        recipient: Some(alice.clone()),
+        ..no_filter(&env)
+    };
+    assert_eq!(
+        client.list_packages(&0, &10, &by_recipient).packages.len(),
+        2
+    );
+
+    let by_status = PackageFilter {
+        statuses: Vec::from_array(&env, [PackageStatus::Claimed]),
+        ..no_filter(&env)
+    };
+    let page = client.list_packages(&0, &10, &by_status);
+    assert_eq!(page.packages.len(), 1);
+    assert_eq!(page.packages.get(0).unwrap().id, 3);
+
+    let created_later = PackageFilter {
+        created_from: Some(start + 50),
+        ..no_filter(&env)
+    };
+    assert_eq!(
+        client.list_packages(&0, &10, &created_later).packages.len(),
+        2
+    );
+
+    let expiring_soon = PackageFilter {
+        expires_from: Some(1),
+        expires_to: Some(start + 500),
+        ..no_filter(&env)
+    };
+    let page = client.list_packages(&0, &10, &expiring_soon);
+    assert_eq!(page.packages.len(), 1);
+    assert_eq!(page.packages.get(0).unwrap().id, 1);
+
+    let other_token = PackageFilter {
+        token: Some(Address::generate(&env)),
+        ..no_filter(&env)
+    };
+    assert_eq!(
+        client.list_packages(&0, &10, &other_token).packages.len(),
+        0
+    );
+}
@@ -0,0 +1,186 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::Error;
+use common::setup_funded;
+use soroban_sdk::{
+    Address, Bytes, BytesN, Env, Vec,
+    testutils::{Address as _, Ledger},
+    xdr::ToXdr,
+};
+
+fn leaf(env: &Env, index: u32, recipient: &Address, amount: i128) -> BytesN<32> {
+    let data = (index, recipient.clone(), amount).to_xdr(env);
+    env.crypto().sha256(&data).to_bytes()
+}
+
+fn parent(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (low, high) = if a < b { (a, b) } else { (b, a) };
+    let mut pair = Bytes::new(env);
+    pair.append(&low.clone().into());
+    pair.append(&high.clone().into());
+    env.crypto().sha256(&pair).to_bytes()
+}
+
+/// Helper: a four-leaf tree over `recipients`, each owed `amounts[i]`.
+/// Returns the root and the proof for every leaf.
+fn build_tree(
+    env: &Env,
+    recipients: &[Address; 4],
+    amounts: &[i128; 4],
+) -> (BytesN<32>, std::vec::Vec<Vec<BytesN<32>>>) {
+    let leaves: std::vec::Vec<BytesN<32>> = (0..4)
+        .map(|i| leaf(env, i as u32, &recipients[i], amounts[i]))
+        .collect();
+    let left = parent(env, &leaves[0], &leaves[1]);
+    let right = parent(env, &leaves[2], &leaves[3]);
+    let root = parent(env, &left, &right);
+
+    let proofs = (0..4)
+        .map(|i| {
+            let sibling = leaves[i ^ 1].clone();
+            let uncle = if i < 2 { right.clone() } else { left.clone() };
+            Vec::from_array(env, [sibling, uncle])
+        })
+        .collect();
+    (root, proofs)
+}
+
+fn households(env: &Env) -> [Address; 4] {
+    [
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+    ]
+}
+
+#[test]
+fn test_claim_merkle() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipients = households(&env);
+    let amounts = [100, 200, 300, 400];
+    let (root, proofs) = build_tree(&env, &recipients, &amounts);
+    let expires_at = env.ledger().timestamp() + 86_400;
+
+    let id = client.create_distribution(&admin, &token_client.address, &root, &1_000, &expires_at);
+    assert_eq!(id, 0);
+
+    for i in 0..4 {
+        client.claim_merkle(&id, &(i as u32), &recipients[i], &amounts[i], &proofs[i]);
+        assert_eq!(token_client.balance(&recipients[i]), amounts[i]);
+        assert!(client.is_merkle_claimed(&id, &(i as u32)));
+    }
+    assert_eq!(client.get_distribution(&id).claimed, 1_000);
+
+    // Each index can only be claimed once
+    assert_eq!(
+        client.try_claim_merkle(&id, &1, &recipients[1], &200, &proofs[1]),
+        Err(Ok(Error::AlreadyClaimed))
+    );
+}
+
+#[test]
+fn test_claim_merkle_rejects_bad_proof() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipients = households(&env);
+    let amounts = [100, 200, 300, 400];
+    let (root, proofs) = build_tree(&env, &recipients, &amounts);
+    let expires_at = env.ledger().timestamp() + 86_400;
+    let id = client.create_distribution(&admin, &token_client.address, &root, &1_000, &expires_at);
+
+    // Wrong amount, recipient, index or proof
+    assert_eq!(
+        client.try_claim_merkle(&id, &0, &recipients[0], &400, &proofs[0]),
+        Err(Ok(Error::InvalidProof))
+    );
+    assert_eq!(
+        client.try_claim_merkle(&id, &0, &recipients[3], &100, &proofs[0]),
+        Err(Ok(Error::InvalidProof))
+    );
+    assert_eq!(
+        client.try_claim_merkle(&id, &1, &recipients[0], &100, &proofs[0]),
+        Err(Ok(Error::InvalidProof))
+    );
+    assert_eq!(
+        client.try_claim_merkle(&id, &0, &recipients[0], &100, &proofs[2]),
+        Err(Ok(Error::InvalidProof))
+    );
+    assert!(!client.is_merkle_claimed(&id, &0));
+
+    assert_eq!(
+        client.try_claim_merkle(&9, &0, &recipients[0], &100, &proofs[0]),
+        Err(Ok(Error::DistributionNotFound))
+    );
+}
+
+#[test]
+fn test_claims_capped_at_locked_total() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipients = households(&env);
+    let amounts = [100, 200, 300, 400];
+    let (root, proofs) = build_tree(&env, &recipients, &amounts);
+    let expires_at = env.ledger().timestamp() + 86_400;
+
+    // The root commits to 1_000 but only 500 is locked
+    let id = client.create_distribution(&admin, &token_client.address, &root, &500, &expires_at);
+    client.claim_merkle(&id, &3, &recipients[3], &400, &proofs[3]);
+    assert_eq!(
+        client.try_claim_merkle(&id, &2, &recipients[2], &300, &proofs[2]),
+        Err(Ok(Error::InsufficientFunds))
+    );
+    client.claim_merkle(&id, &0, &recipients[0], &100, &proofs[0]);
+}
+
+#[test]
+fn test_close_distribution_after_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipients = households(&env);
+    let amounts = [100, 200, 300, 400];
+    let (root, proofs) = build_tree(&env, &recipients, &amounts);
+    let expires_at = env.ledger().timestamp() + 86_400;
+
+    let id = client.create_distribution(&admin, &token_client.address, &root, &1_000, &expires_at);
+    client.claim_merkle(&id, &0, &recipients[0], &100, &proofs[0]);
+
+    // The locked total is not available to packages
+    let other = Address::generate(&env);
+    assert_eq!(
+        client.try_create_package(&admin, &1, &other, &9_001, &token_client.address, &0),
+        Err(Ok(Error::InsufficientFunds))
+    );
+
+    assert_eq!(
+        client.try_close_distribution(&id),
+        Err(Ok(Error::InvalidState))
+    );
+
+    env.ledger().set_timestamp(expires_at + 1);
+    assert_eq!(
+        client.try_claim_merkle(&id, &1, &recipients[1], &200, &proofs[1]),
+        Err(Ok(Error::PackageExpired))
+    );
+
+    assert_eq!(client.close_distribution(&id), 900);
+    assert!(client.get_distribution(&id).closed);
+    assert_eq!(
+        client.try_close_distribution(&id),
+        Err(Ok(Error::InvalidState))
+    );
+
+    // The remainder is back in the pool
+    client.create_package(&admin, &1, &other, &9_900, &token_client.address, &0);
+}
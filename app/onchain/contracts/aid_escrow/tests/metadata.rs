@@ -0,0 +1,179 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{Error, METADATA_DOC_HASH, Role};
+use common::setup_funded;
+use soroban_sdk::{Address, Env, String, Symbol, testutils::Address as _};
+
+const DIGEST: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+#[test]
+fn test_set_and_remove_metadata() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let region = Symbol::new(&env, "region");
+
+    client.create_package(&admin, &1, &recipient, &500, &token_client.address, &0);
+    client.set_package_metadata(&admin, &1, &region, &String::from_str(&env, "north"));
+    client.set_package_metadata(
+        &admin,
+        &1,
+        &METADATA_DOC_HASH,
+        &String::from_str(&env, DIGEST),
+    );
+
+    let metadata = client.get_package(&1).metadata;
+    assert_eq!(metadata.len(), 2);
+    assert_eq!(
+        metadata.get(region.clone()).unwrap(),
+        String::from_str(&env, "north")
+    );
+
+    // Evidence can still be anchored once the package is claimed
+    client.claim(&1);
+    client.set_package_metadata(&admin, &1, &region, &String::from_str(&env, "south"));
+    client.remove_package_metadata(&admin, &1, &region);
+
+    let metadata = client.get_package(&1).metadata;
+    assert_eq!(metadata.len(), 1);
+    assert!(metadata.contains_key(METADATA_DOC_HASH));
+    assert_eq!(
+        client.try_remove_package_metadata(&admin, &1, &region),
+        Err(Ok(Error::InvalidState))
+    );
+}
+
+#[test]
+fn test_metadata_limited_to_admin_and_creator() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let distributor = Address::generate(&env);
+    let other_distributor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let note = Symbol::new(&env, "note");
+    let value = String::from_str(&env, "verified");
+
+    client.grant_role(&Role::Distributor, &distributor);
+    client.grant_role(&Role::Distributor, &other_distributor);
+    client.set_distributor_allowance(&distributor, &token_client.address, &1_000, &0, &0);
+    client.create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &500,
+        &token_client.address,
+        &0,
+    );
+
+    client.set_package_metadata(&distributor, &1, &note, &value);
+    client.set_package_metadata(&admin, &1, &note, &value);
+    assert_eq!(
+        client.try_set_package_metadata(&other_distributor, &1, &note, &value),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_remove_package_metadata(&recipient, &1, &note),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_set_package_metadata(&admin, &9, &note, &value),
+        Err(Ok(Error::PackageNotFound))
+    );
+
+    // The creator loses access along with the role
+    client.revoke_role(&Role::Distributor, &distributor);
+    assert_eq!(
+        client.try_set_package_metadata(&distributor, &1, &note, &value),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_remove_package_metadata(&distributor, &1, &note),
+        Err(Ok(Error::NotAuthorized))
+    );
+    client.remove_package_metadata(&admin, &1, &note);
+}
+
+#[test]
+fn test_metadata_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let value = String::from_str(&env, "x");
+
+    client.create_package(&admin, &1, &recipient, &500, &token_client.address, &0);
+
+    let long = String::from_str(&env, &"a".repeat(257));
+    assert_eq!(
+        client.try_set_package_metadata(&admin, &1, &Symbol::new(&env, "long"), &long),
+        Err(Ok(Error::MetadataLimitExceeded))
+    );
+
+    for i in 0..16 {
+        let key = Symbol::new(&env, &std::format!("key{}", i));
+        client.set_package_metadata(&admin, &1, &key, &value);
+    }
+    assert_eq!(
+        client.try_set_package_metadata(&admin, &1, &Symbol::new(&env, "key16"), &value),
+        Err(Ok(Error::MetadataLimitExceeded))
+    );
+    // Existing keys can still be overwritten
+    client.set_package_metadata(&admin, &1, &Symbol::new(&env, "key0"), &value);
+}
+
+#[test]
+fn test_doc_hash_must_be_hex_digest() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &500, &token_client.address, &0);
+
+    for bad in ["photo.jpg", &DIGEST[..63], &DIGEST.replace('9', "g")] {
+        assert_eq!(
+            client.try_set_package_metadata(
+                &admin,
+                &1,
+                &METADATA_DOC_HASH,
+                &String::from_str(&env, bad)
+            ),
+            Err(Ok(Error::InvalidMetadata))
+        );
+    }
+}
+
+#[test]
+fn test_doc_hash_is_write_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let digest = String::from_str(&env, DIGEST);
+
+    client.create_package(&admin, &1, &recipient, &500, &token_client.address, &0);
+    client.set_package_metadata(&admin, &1, &METADATA_DOC_HASH, &digest);
+
+    let other = String::from_str(&env, &DIGEST.replace('9', "a"));
+    assert_eq!(
+        client.try_set_package_metadata(&admin, &1, &METADATA_DOC_HASH, &other),
+        Err(Ok(Error::InvalidState))
+    );
+    assert_eq!(
+        client.try_remove_package_metadata(&admin, &1, &METADATA_DOC_HASH),
+        Err(Ok(Error::InvalidState))
+    );
+    assert_eq!(
+        client.get_package(&1).metadata.get(METADATA_DOC_HASH),
+        Some(digest)
+    );
+}
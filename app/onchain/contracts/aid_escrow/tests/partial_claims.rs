@@ -0,0 +1,148 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{Error, PackageStatus};
+use common::setup_funded;
+use soroban_sdk::{
+    Address, Env, String,
+    testutils::{Address as _, Ledger},
+};
+
+#[test]
+fn test_claim_in_installments() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &1_000, &token_client.address, &0);
+
+    client.claim_partial(&1, &300);
+    let pkg = client.get_package(&1);
+    assert_eq!(pkg.status, PackageStatus::PartiallyClaimed);
+    assert_eq!(pkg.claimed_amount, 300);
+    assert_eq!(token_client.balance(&recipient), 300);
+
+    let agg = client.get_aggregates(&token_client.address);
+    assert_eq!(agg.total_committed, 700);
+    assert_eq!(agg.total_claimed, 300);
+    assert_eq!(
+        client.get_claimable_total(&recipient, &token_client.address),
+        700
+    );
+
+    // Claiming the exact remainder completes the package
+    client.claim_partial(&1, &700);
+    let pkg = client.get_package(&1);
+    assert_eq!(pkg.status, PackageStatus::Claimed);
+    assert_eq!(pkg.claimed_amount, 1_000);
+    assert_eq!(token_client.balance(&recipient), 1_000);
+
+    let agg = client.get_aggregates(&token_client.address);
+    assert_eq!(agg.total_committed, 0);
+    assert_eq!(agg.total_claimed, 1_000);
+    assert_eq!(agg.claimed_count, 1);
+
+    // The whole pool is unlocked again
+    client.withdraw_surplus(&admin, &9_000, &token_client.address);
+}
+
+#[test]
+fn test_claim_partial_invalid_amounts() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &1_000, &token_client.address, &0);
+    client.claim_partial(&1, &400);
+
+    assert_eq!(
+        client.try_claim_partial(&1, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_claim_partial(&1, &601),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    // A full claim pays only what is left
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 1_000);
+    assert_eq!(
+        client.try_claim_partial(&1, &1),
+        Err(Ok(Error::PackageNotActive))
+    );
+}
+
+#[test]
+fn test_revoke_and_refund_touch_only_remainder() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    let campaign_id = client.create_campaign(
+        &String::from_str(&env, "Cash for work"),
+        &token_client.address,
+        &2_000,
+        &now,
+        &0,
+    );
+    client.create_campaign_package(&admin, &campaign_id, &1, &recipient, &1_000, &0);
+    client.claim_partial(&1, &250);
+
+    client.revoke(&1);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Cancelled);
+
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.locked, 0);
+    assert_eq!(campaign.spent, 250);
+
+    let agg = client.get_aggregates(&token_client.address);
+    assert_eq!(agg.total_claimed, 250);
+    assert_eq!(agg.total_expired_cancelled, 750);
+    assert_eq!(agg.total_committed, 0);
+
+    client.refund(&admin, &1);
+    assert_eq!(token_client.balance(&admin), 750);
+    assert_eq!(token_client.balance(&recipient), 250);
+}
+
+#[test]
+fn test_refund_expired_partial_package() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    client.create_package(
+        &admin,
+        &1,
+        &recipient,
+        &1_000,
+        &token_client.address,
+        &(now + 100),
+    );
+    client.claim_partial(&1, &600);
+
+    env.ledger().set_timestamp(now + 101);
+    assert_eq!(
+        client.try_claim_partial(&1, &100),
+        Err(Ok(Error::PackageExpired))
+    );
+
+    client.refund(&admin, &1);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Refunded);
+    assert_eq!(token_client.balance(&admin), 400);
+
+    // Everything not paid out is back in the admin's hands
+    assert_eq!(token_client.balance(&client.address), 9_000);
+}
@@ -0,0 +1,215 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{AidEscrowClient, BatchCreateResult, Config, Error};
+use common::{setup_funded, setup_token};
+use soroban_sdk::{Address, BytesN, Env, Vec, testutils::Address as _};
+
+/// Asserts that a single package, a one-row batch and a Merkle distribution
+/// with the same amount, token and lifetime are all rejected with `error`.
+fn assert_rejected_everywhere(
+    env: &Env,
+    client: &AidEscrowClient,
+    admin: &Address,
+    amount: i128,
+    token: &Address,
+    expires_in: u64,
+    error: Error,
+) {
+    let recipient = Address::generate(env);
+    let expires_at = env.ledger().timestamp() + expires_in;
+
+    assert_eq!(
+        client.try_create_package(admin, &1, &recipient, &amount, token, &expires_at),
+        Err(Ok(error))
+    );
+
+    let recipients = Vec::from_array(env, [recipient]);
+    let amounts = Vec::from_array(env, [amount]);
+    assert_eq!(
+        client.try_batch_create_packages(admin, &recipients, &amounts, token, &expires_in),
+        Err(Ok(error))
+    );
+    assert_eq!(
+        client.batch_create_best_effort(admin, &recipients, &amounts, token, &expires_in),
+        Vec::from_array(env, [BatchCreateResult::Failed(error as u32)])
+    );
+
+    let merkle_root = BytesN::from_array(env, &[1u8; 32]);
+    assert_eq!(
+        client.try_create_distribution(admin, token, &merkle_root, &amount, &expires_at),
+        Err(Ok(error))
+    );
+}
+
+fn config(env: &Env) -> Config {
+    Config {
+        min_amount: 1,
+        max_expires_in: 0,
+        allowed_tokens: Vec::new(env),
+    }
+}
+
+#[test]
+fn test_batch_honors_min_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    client.set_config(
+        &admin,
+        &Config {
+            min_amount: 100,
+            ..config(&env)
+        },
+    );
+
+    assert_rejected_everywhere(
+        &env,
+        &client,
+        &admin,
+        99,
+        &token_client.address,
+        3600,
+        Error::InvalidAmount,
+    );
+    // Voucher budgets have no lifetime but are held to the same minimum
+    assert_eq!(
+        client.try_fund_voucher_budget(&admin, &token_client.address, &99),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    let recipients = Vec::from_array(&env, [Address::generate(&env)]);
+    let amounts = Vec::from_array(&env, [100]);
+    client.batch_create_packages(&admin, &recipients, &amounts, &token_client.address, &3600);
+}
+
+#[test]
+fn test_batch_honors_allowed_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let other_admin = Address::generate(&env);
+    let (other_token, other_token_admin) = setup_token(&env, &other_admin);
+    other_token_admin.mint(&admin, &1_000);
+    client.fund(&other_token.address, &admin, &1_000);
+
+    client.set_config(
+        &admin,
+        &Config {
+            allowed_tokens: Vec::from_array(&env, [token_client.address.clone()]),
+            ..config(&env)
+        },
+    );
+
+    assert_rejected_everywhere(
+        &env,
+        &client,
+        &admin,
+        100,
+        &other_token.address,
+        3600,
+        Error::InvalidState,
+    );
+    assert_eq!(
+        client.try_fund_voucher_budget(&admin, &other_token.address, &100),
+        Err(Ok(Error::InvalidState))
+    );
+
+    let recipients = Vec::from_array(&env, [Address::generate(&env)]);
+    let amounts = Vec::from_array(&env, [100]);
+    client.batch_create_packages(&admin, &recipients, &amounts, &token_client.address, &3600);
+}
+
+#[test]
+fn test_batch_honors_max_expires_in() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    client.set_config(
+        &admin,
+        &Config {
+            max_expires_in: 3600,
+            ..config(&env)
+        },
+    );
+
+    assert_rejected_everywhere(
+        &env,
+        &client,
+        &admin,
+        100,
+        &token_client.address,
+        3601,
+        Error::InvalidState,
+    );
+    // A package that expires immediately is rejected too
+    assert_rejected_everywhere(
+        &env,
+        &client,
+        &admin,
+        100,
+        &token_client.address,
+        0,
+        Error::InvalidState,
+    );
+
+    let recipients = Vec::from_array(&env, [Address::generate(&env)]);
+    let amounts = Vec::from_array(&env, [100]);
+    client.batch_create_packages(&admin, &recipients, &amounts, &token_client.address, &3600);
+}
+
+#[test]
+fn test_batch_numbers_after_manual_ids() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+
+    // A manually created package would occupy the next batch ID
+    client.create_package(&admin, &0, &recipient, &100, &token_client.address, &0);
+    client.create_package(&admin, &5, &recipient, &100, &token_client.address, &0);
+
+    let recipients = Vec::from_array(&env, [Address::generate(&env), Address::generate(&env)]);
+    let amounts = Vec::from_array(&env, [100, 200]);
+    assert_eq!(
+        client.batch_create_packages(&admin, &recipients, &amounts, &token_client.address, &3600),
+        Vec::from_array(&env, [6, 7])
+    );
+    assert_eq!(client.get_package(&0).recipient, Some(recipient));
+    assert_eq!(client.get_package(&7).amount, 200);
+}
+
+#[test]
+fn test_explicit_ids_cannot_exhaust_batch_ids() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let token = token_client.address.clone();
+    let recipient = Address::generate(&env);
+
+    for id in [u64::MAX, u64::MAX - 1, u64::MAX / 2 + 1] {
+        assert_eq!(
+            client.try_create_package(&admin, &id, &recipient, &100, &token, &0),
+            Err(Ok(Error::PackageIdOutOfRange))
+        );
+    }
+
+    // The highest explicit ID still leaves batch numbering room to continue
+    let last = u64::MAX / 2;
+    client.create_package(&admin, &last, &recipient, &100, &token, &0);
+
+    let recipients = Vec::from_array(&env, [Address::generate(&env), Address::generate(&env)]);
+    let amounts = Vec::from_array(&env, [100, 200]);
+    let diagnostics = client.validate_batch(&admin, &recipients, &amounts, &token, &3600);
+    assert_eq!(diagnostics.rows.get(0).unwrap().id, last + 1);
+    assert_eq!(
+        client.batch_create_packages(&admin, &recipients, &amounts, &token, &3600),
+        Vec::from_array(&env, [last + 1, last + 2])
+    );
+}
@@ -0,0 +1,108 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::Error;
+use common::setup_funded;
+use soroban_sdk::{Address, Env, String, testutils::Address as _};
+
+#[test]
+fn test_reassign_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let wrong = Address::generate(&env);
+    let right = Address::generate(&env);
+    let reason = String::from_str(&env, "typo in registration");
+
+    client.create_package(&admin, &1, &wrong, &500, &token_client.address, &0);
+    client.reassign_recipient(&1, &right, &reason, &false);
+
+    // Only the admin authorized it
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, admin);
+
+    let package = client.get_package(&1);
+    assert_eq!(package.recipient, Some(right.clone()));
+    assert_eq!(package.previous_recipients.len(), 1);
+    assert_eq!(package.previous_recipients.get(0).unwrap(), wrong);
+
+    // The recipient index follows the package
+    assert_eq!(
+        client
+            .get_packages_for_recipient(&wrong, &0, &10)
+            .packages
+            .len(),
+        0
+    );
+    assert_eq!(
+        client
+            .get_packages_for_recipient(&right, &0, &10)
+            .packages
+            .len(),
+        1
+    );
+    assert_eq!(client.get_claimable_total(&wrong, &token_client.address), 0);
+    assert_eq!(
+        client.get_claimable_total(&right, &token_client.address),
+        500
+    );
+
+    client.claim(&1);
+    assert_eq!(token_client.balance(&right), 500);
+}
+
+#[test]
+fn test_reassign_with_consent() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let old = Address::generate(&env);
+    let new = Address::generate(&env);
+    let reason = String::from_str(&env, "lost phone");
+
+    client.create_package(&admin, &1, &old, &500, &token_client.address, &0);
+    client.reassign_recipient(&1, &new, &reason, &true);
+
+    let auths = env.auths();
+    assert_eq!(auths.len(), 2);
+    assert_eq!(auths[1].0, old);
+}
+
+#[test]
+fn test_reassign_only_unclaimed_packages() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let other = Address::generate(&env);
+    let reason = String::from_str(&env, "duplicate registration");
+
+    client.create_package(&admin, &1, &recipient, &500, &token_client.address, &0);
+    client.claim_partial(&1, &100);
+    assert_eq!(
+        client.try_reassign_recipient(&1, &other, &reason, &false),
+        Err(Ok(Error::InvalidState))
+    );
+
+    client.create_package(&admin, &2, &recipient, &500, &token_client.address, &0);
+    client.revoke(&2);
+    assert_eq!(
+        client.try_reassign_recipient(&2, &other, &reason, &false),
+        Err(Ok(Error::InvalidState))
+    );
+
+    client.create_package(&admin, &3, &recipient, &500, &token_client.address, &0);
+    assert_eq!(
+        client.try_reassign_recipient(&3, &recipient, &reason, &false),
+        Err(Ok(Error::InvalidState))
+    );
+    assert_eq!(
+        client.try_reassign_recipient(&9, &other, &reason, &false),
+        Err(Ok(Error::PackageNotFound))
+    );
+}
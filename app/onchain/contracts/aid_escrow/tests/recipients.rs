@@ -0,0 +1,116 @@
+#![cfg(test)]
+
+mod common;
+
+use common::setup_funded;
+use soroban_sdk::{
+    Address, Env, String, Vec,
+    testutils::{Address as _, Ledger},
+};
+
+#[test]
+fn test_recipient_index_covers_all_creation_paths() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    client.create_package(&admin, &10, &alice, &100, &token_client.address, &0);
+
+    let mut recipients = Vec::new(&env);
+    recipients.push_back(bob.clone());
+    recipients.push_back(alice.clone());
+    let mut amounts = Vec::new(&env);
+    amounts.push_back(200);
+    amounts.push_back(300);
+    let batch_ids =
+        client.batch_create_packages(&admin, &recipients, &amounts, &token_client.address, &3600);
+
+    let campaign_id = client.create_campaign(
+        &String::from_str(&env, "Flood response"),
+        &token_client.address,
+        &1_000,
+        &now,
+        &0,
+    );
+    client.create_campaign_package(&admin, &campaign_id, &20, &alice, &400, &0);
+
+    let page = client.get_packages_for_recipient(&alice, &0, &10);
+    assert_eq!(page.next_cursor, None);
+    assert_eq!(page.packages.len(), 3);
+    assert_eq!(page.packages.get(0).unwrap().id, 10);
+    assert_eq!(page.packages.get(1).unwrap().id, batch_ids.get(1).unwrap());
+    assert_eq!(page.packages.get(2).unwrap().id, 20);
+
+    let page = client.get_packages_for_recipient(&bob, &0, &10);
+    assert_eq!(page.packages.len(), 1);
+    assert_eq!(page.packages.get(0).unwrap().amount, 200);
+
+    // Paging through a recipient's packages
+    let page = client.get_packages_for_recipient(&alice, &0, &2);
+    assert_eq!(page.packages.len(), 2);
+    assert_eq!(page.next_cursor, Some(2));
+
+    // Unknown recipient
+    let page = client.get_packages_for_recipient(&Address::generate(&env), &0, &10);
+    assert_eq!(page.packages.len(), 0);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_claimable_total() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    client.create_package(&admin, &1, &recipient, &100, &token_client.address, &0);
+    client.create_package(
+        &admin,
+        &2,
+        &recipient,
+        &200,
+        &token_client.address,
+        &(now + 100),
+    );
+    client.create_package(
+        &admin,
+        &3,
+        &recipient,
+        &400,
+        &token_client.address,
+        &(now + 1_000),
+    );
+    client.create_package(&admin, &4, &recipient, &800, &token_client.address, &0);
+
+    assert_eq!(
+        client.get_claimable_total(&recipient, &token_client.address),
+        1_500
+    );
+
+    // Claimed and revoked packages no longer count
+    client.claim(&1);
+    client.revoke(&4);
+    assert_eq!(
+        client.get_claimable_total(&recipient, &token_client.address),
+        600
+    );
+
+    // Expired packages no longer count
+    env.ledger().set_timestamp(now + 101);
+    assert_eq!(
+        client.get_claimable_total(&recipient, &token_client.address),
+        400
+    );
+
+    // Other tokens are ignored
+    assert_eq!(
+        client.get_claimable_total(&recipient, &Address::generate(&env)),
+        0
+    );
+}
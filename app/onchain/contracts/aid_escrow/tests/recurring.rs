@@ -0,0 +1,159 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{Error, PackageStatus, RecurringSchedule};
+use common::setup_funded;
+use soroban_sdk::{
+    Address, Env,
+    testutils::{Address as _, Ledger},
+};
+
+const MONTH: u64 = 30 * 24 * 60 * 60;
+
+#[test]
+fn test_stipend_claimed_once_per_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let start = env.ledger().timestamp() + 100;
+    let schedule = RecurringSchedule {
+        start,
+        period: MONTH,
+        periods: 6,
+    };
+
+    client.create_recurring_package(
+        &admin,
+        &1,
+        &recipient,
+        &500,
+        &token_client.address,
+        &schedule,
+        &0,
+    );
+
+    // All six months are locked up front
+    assert_eq!(client.get_package(&1).amount, 3_000);
+    assert_eq!(
+        client.try_withdraw_surplus(&admin, &7_001, &token_client.address),
+        Err(Ok(Error::InsufficientSurplus))
+    );
+
+    assert_eq!(client.try_claim(&1), Err(Ok(Error::NothingToClaim)));
+
+    // First month
+    env.ledger().set_timestamp(start);
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 500);
+    assert_eq!(client.try_claim(&1), Err(Ok(Error::NothingToClaim)));
+
+    // A missed month carries over
+    env.ledger().set_timestamp(start + 2 * MONTH + 5);
+    assert_eq!(client.claimable_now(&1), 1_000);
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 1_500);
+
+    // After the last period everything has been released
+    env.ledger().set_timestamp(start + 10 * MONTH);
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 3_000);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Claimed);
+}
+
+#[test]
+fn test_stop_stipend_unlocks_remaining_periods() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let start = env.ledger().timestamp();
+    let schedule = RecurringSchedule {
+        start,
+        period: MONTH,
+        periods: 4,
+    };
+
+    client.create_recurring_package(
+        &admin,
+        &1,
+        &recipient,
+        &1_000,
+        &token_client.address,
+        &schedule,
+        &0,
+    );
+
+    client.claim(&1);
+    env.ledger().set_timestamp(start + MONTH);
+
+    // Stopping pays the released second month and unlocks the last two
+    client.revoke(&1);
+    assert_eq!(token_client.balance(&recipient), 2_000);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Cancelled);
+    client.withdraw_surplus(&admin, &8_000, &token_client.address);
+}
+
+#[test]
+fn test_invalid_recurring_schedules() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let start = env.ledger().timestamp();
+
+    let no_period = RecurringSchedule {
+        start,
+        period: 0,
+        periods: 3,
+    };
+    assert_eq!(
+        client.try_create_recurring_package(
+            &admin,
+            &1,
+            &recipient,
+            &100,
+            &token_client.address,
+            &no_period,
+            &0,
+        ),
+        Err(Ok(Error::InvalidState))
+    );
+
+    // Expires before the last installment is released
+    let schedule = RecurringSchedule {
+        start,
+        period: MONTH,
+        periods: 3,
+    };
+    assert_eq!(
+        client.try_create_recurring_package(
+            &admin,
+            &1,
+            &recipient,
+            &100,
+            &token_client.address,
+            &schedule,
+            &(start + MONTH),
+        ),
+        Err(Ok(Error::InvalidState))
+    );
+
+    // Locking all periods must fit in the pool
+    assert_eq!(
+        client.try_create_recurring_package(
+            &admin,
+            &1,
+            &recipient,
+            &5_000,
+            &token_client.address,
+            &schedule,
+            &0,
+        ),
+        Err(Ok(Error::InsufficientFunds))
+    );
+}
@@ -0,0 +1,183 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{Config, Error, PackageStatus, Role};
+use common::setup_funded;
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Ledger},
+};
+
+#[test]
+fn test_grant_and_revoke_role() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, _admin) = setup_funded(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    assert!(!client.has_role(&Role::Pauser, &alice));
+
+    client.grant_role(&Role::Pauser, &alice);
+    client.grant_role(&Role::Pauser, &bob);
+    // Granting twice does not duplicate membership
+    client.grant_role(&Role::Pauser, &alice);
+
+    assert!(client.has_role(&Role::Pauser, &alice));
+    assert!(!client.has_role(&Role::Refunder, &alice));
+    assert_eq!(client.list_role_members(&Role::Pauser).len(), 2);
+
+    client.revoke_role(&Role::Pauser, &alice);
+    assert!(!client.has_role(&Role::Pauser, &alice));
+    let members = client.list_role_members(&Role::Pauser);
+    assert_eq!(members.len(), 1);
+    assert_eq!(members.get(0).unwrap(), bob);
+}
+
+#[test]
+fn test_distributor_shorthands_use_role() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _admin) = setup_funded(&env);
+    let distributor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    client.add_distributor(&distributor);
+    assert!(client.has_role(&Role::Distributor, &distributor));
+    client.set_distributor_allowance(&distributor, &token_client.address, &1_000, &0, &0);
+
+    client.create_package(
+        &distributor,
+        &1,
+        &recipient,
+        &100,
+        &token_client.address,
+        &(env.ledger().timestamp() + 86400),
+    );
+
+    client.remove_distributor(&distributor);
+    assert!(!client.has_role(&Role::Distributor, &distributor));
+
+    let result = client.try_create_package(
+        &distributor,
+        &2,
+        &recipient,
+        &100,
+        &token_client.address,
+        &(env.ledger().timestamp() + 86400),
+    );
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+}
+
+#[test]
+fn test_pauser_can_pause_but_nothing_else() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let field_officer = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    client.create_package(
+        &admin,
+        &1,
+        &recipient,
+        &100,
+        &token_client.address,
+        &(now + 10),
+    );
+    env.ledger().set_timestamp(now + 11);
+
+    client.grant_role(&Role::Pauser, &field_officer);
+
+    // No config or refund rights
+    let config = Config {
+        min_amount: 1,
+        max_expires_in: 0,
+        allowed_tokens: Vec::new(&env),
+    };
+    assert_eq!(
+        client.try_set_config(&field_officer, &config),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_refund(&field_officer, &1),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_create_package(
+            &field_officer,
+            &2,
+            &recipient,
+            &100,
+            &token_client.address,
+            &0,
+        ),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    client.pause(&field_officer);
+    assert!(client.is_paused());
+}
+
+#[test]
+fn test_pauser_works_alongside_council() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, admin) = setup_funded(&env);
+    let field_officer = Address::generate(&env);
+
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+    signers.push_back(Address::generate(&env));
+    client.grant_role(&Role::Pauser, &field_officer);
+    client.set_council(&signers, &2);
+
+    // The admin key alone can no longer pause, but a Pauser can
+    assert_eq!(client.try_pause(&admin), Err(Ok(Error::CouncilRequired)));
+    client.pause(&field_officer);
+    assert!(client.is_paused());
+}
+
+#[test]
+fn test_refunder_and_config_manager() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let refunder = Address::generate(&env);
+    let config_manager = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    client.grant_role(&Role::Refunder, &refunder);
+    client.grant_role(&Role::ConfigManager, &config_manager);
+
+    let config = Config {
+        min_amount: 10,
+        max_expires_in: 0,
+        allowed_tokens: Vec::new(&env),
+    };
+    client.set_config(&config_manager, &config);
+    assert_eq!(client.get_config(), config);
+
+    client.create_package(
+        &admin,
+        &1,
+        &recipient,
+        &500,
+        &token_client.address,
+        &(env.ledger().timestamp() + 86400),
+    );
+    client.revoke(&1);
+    client.refund(&refunder, &1);
+
+    // Refunded funds go to the admin, never to the refunder
+    assert_eq!(client.get_package(&1).status, PackageStatus::Refunded);
+    assert_eq!(token_client.balance(&admin), 500);
+    assert_eq!(token_client.balance(&refunder), 0);
+}
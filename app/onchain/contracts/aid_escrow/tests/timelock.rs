@@ -0,0 +1,322 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{
+    AidEscrow, AidEscrowClient, Config, CouncilAction, Error, OperationStatus, Role, TimelockOp,
+};
+use common::setup_token;
+use soroban_sdk::{
+    Address, BytesN, Env, String, Vec,
+    testutils::{Address as _, Ledger},
+    token::TokenClient,
+};
+
+const DELAY: u64 = 2 * 86400;
+
+/// Helper: funded contract with the timelock enabled.
+fn setup_timelocked(env: &Env) -> (AidEscrowClient<'static>, TokenClient<'static>, Address) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+
+    client.init(&admin);
+    token_admin_client.mint(&admin, &10_000);
+    client.fund(&token_client.address, &admin, &10_000);
+    client.set_timelock_delay(&DELAY);
+
+    (client, token_client, admin)
+}
+
+#[test]
+fn test_direct_calls_blocked_while_timelocked() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_timelocked(&env);
+    let distributor = Address::generate(&env);
+    client.add_distributor(&distributor);
+
+    assert_eq!(
+        client.try_withdraw_surplus(&admin, &100, &token_client.address),
+        Err(Ok(Error::TimelockRequired))
+    );
+    let config = Config {
+        min_amount: 5,
+        max_expires_in: 0,
+        allowed_tokens: Vec::new(&env),
+    };
+    assert_eq!(
+        client.try_set_config(&admin, &config),
+        Err(Ok(Error::TimelockRequired))
+    );
+    assert_eq!(
+        client.try_remove_distributor(&distributor),
+        Err(Ok(Error::TimelockRequired))
+    );
+    assert_eq!(
+        client.try_revoke_role(&Role::Distributor, &distributor),
+        Err(Ok(Error::TimelockRequired))
+    );
+    assert_eq!(
+        client.try_set_timelock_delay(&0),
+        Err(Ok(Error::TimelockRequired))
+    );
+
+    // Other roles are not timelocked
+    let pauser = Address::generate(&env);
+    client.grant_role(&Role::Pauser, &pauser);
+    client.revoke_role(&Role::Pauser, &pauser);
+}
+
+#[test]
+fn test_queue_and_execute_withdrawal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _admin) = setup_timelocked(&env);
+    let treasury = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    let op_id = client.queue_operation(&TimelockOp::WithdrawSurplus(
+        treasury.clone(),
+        1_000,
+        token_client.address.clone(),
+    ));
+    let operation = client.get_operation(&op_id);
+    assert_eq!(operation.eta, now + DELAY);
+    assert_eq!(operation.status, OperationStatus::Queued);
+
+    // Too early
+    env.ledger().set_timestamp(now + DELAY - 1);
+    assert_eq!(
+        client.try_execute_operation(&op_id),
+        Err(Ok(Error::TimelockNotReady))
+    );
+    assert_eq!(token_client.balance(&treasury), 0);
+
+    env.ledger().set_timestamp(now + DELAY);
+    client.execute_operation(&op_id);
+    assert_eq!(token_client.balance(&treasury), 1_000);
+    assert_eq!(
+        client.get_operation(&op_id).status,
+        OperationStatus::Executed
+    );
+
+    // Cannot execute twice
+    assert_eq!(
+        client.try_execute_operation(&op_id),
+        Err(Ok(Error::OperationNotQueued))
+    );
+}
+
+#[test]
+fn test_cancel_during_delay() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_timelocked(&env);
+    let now = env.ledger().timestamp();
+
+    let op_id = client.queue_operation(&TimelockOp::WithdrawSurplus(
+        admin.clone(),
+        5_000,
+        token_client.address.clone(),
+    ));
+    client.cancel_operation(&op_id);
+    assert_eq!(
+        client.get_operation(&op_id).status,
+        OperationStatus::Cancelled
+    );
+
+    env.ledger().set_timestamp(now + DELAY + 1);
+    assert_eq!(
+        client.try_execute_operation(&op_id),
+        Err(Ok(Error::OperationNotQueued))
+    );
+    assert_eq!(
+        client.try_cancel_operation(&op_id),
+        Err(Ok(Error::OperationNotQueued))
+    );
+    assert_eq!(token_client.balance(&admin), 0);
+    assert_eq!(
+        client.try_get_operation(&99),
+        Err(Ok(Error::OperationNotFound))
+    );
+}
+
+#[test]
+fn test_queued_config_and_distributor_removal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, _admin) = setup_timelocked(&env);
+    let distributor = Address::generate(&env);
+    client.add_distributor(&distributor);
+    let now = env.ledger().timestamp();
+
+    let config = Config {
+        min_amount: 25,
+        max_expires_in: 0,
+        allowed_tokens: Vec::new(&env),
+    };
+    let config_op = client.queue_operation(&TimelockOp::SetConfig(config.clone()));
+    let removal_op = client.queue_operation(&TimelockOp::RemoveDistributor(distributor.clone()));
+
+    env.ledger().set_timestamp(now + DELAY);
+    client.execute_operation(&config_op);
+    client.execute_operation(&removal_op);
+
+    assert_eq!(client.get_config(), config);
+    assert!(!client.has_role(&Role::Distributor, &distributor));
+}
+
+#[test]
+fn test_delay_changes_are_timelocked() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, _admin) = setup_timelocked(&env);
+    let now = env.ledger().timestamp();
+
+    let op_id = client.queue_operation(&TimelockOp::SetDelay(0));
+    assert_eq!(client.get_timelock_delay(), DELAY);
+
+    env.ledger().set_timestamp(now + DELAY);
+    client.execute_operation(&op_id);
+    assert_eq!(client.get_timelock_delay(), 0);
+
+    // Excessive delays are rejected
+    assert_eq!(
+        client.try_set_timelock_delay(&(365 * 86400)),
+        Err(Ok(Error::InvalidState))
+    );
+}
+
+#[test]
+fn test_council_actions_are_queued_when_timelocked() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _admin) = setup_timelocked(&env);
+    let treasury = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+    signers.push_back(Address::generate(&env));
+    client.set_council(&signers, &2);
+
+    // The sole admin cannot bypass the council by queueing directly
+    assert_eq!(
+        client.try_queue_operation(&TimelockOp::WithdrawSurplus(
+            treasury.clone(),
+            100,
+            token_client.address.clone(),
+        )),
+        Err(Ok(Error::CouncilRequired))
+    );
+
+    let proposal_id = client.propose(
+        &signers.get(0).unwrap(),
+        &CouncilAction::WithdrawSurplus(treasury.clone(), 100, token_client.address.clone()),
+    );
+    client.approve(&signers.get(1).unwrap(), &proposal_id);
+    client.execute(&proposal_id);
+
+    // Approved, but not yet paid out
+    assert_eq!(token_client.balance(&treasury), 0);
+    let operation = client.get_operation(&0);
+    assert_eq!(operation.status, OperationStatus::Queued);
+
+    env.ledger().set_timestamp(now + DELAY);
+    client.execute_operation(&0);
+    assert_eq!(token_client.balance(&treasury), 100);
+}
+
+#[test]
+fn test_admin_fund_paths_held_by_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_timelocked(&env);
+    let token = token_client.address.clone();
+    let recipient = Address::generate(&env);
+    let now = env.ledger().timestamp();
+
+    // The admin cannot lock funds to itself without an allowance
+    assert_eq!(
+        client.try_create_package(&admin, &1, &admin, &1_000, &token, &0),
+        Err(Ok(Error::AllowanceExceeded))
+    );
+    assert_eq!(
+        client.try_fund_voucher_budget(&admin, &token, &1_000),
+        Err(Ok(Error::AllowanceExceeded))
+    );
+    assert_eq!(
+        client.try_create_distribution(
+            &admin,
+            &token,
+            &BytesN::from_array(&env, &[0u8; 32]),
+            &1_000,
+            &(now + 86400)
+        ),
+        Err(Ok(Error::AllowanceExceeded))
+    );
+
+    // Raising an allowance must wait out the delay; lowering need not
+    assert_eq!(
+        client.try_set_distributor_allowance(&admin, &token, &2_000, &0, &0),
+        Err(Ok(Error::TimelockRequired))
+    );
+    client.set_distributor_allowance(&admin, &token, &0, &0, &0);
+    let op_id = client.queue_operation(&TimelockOp::SetAllowance(
+        admin.clone(),
+        token.clone(),
+        2_000,
+        0,
+        0,
+    ));
+    env.ledger().set_timestamp(now + DELAY);
+    client.execute_operation(&op_id);
+
+    client.create_package(&admin, &1, &recipient, &1_000, &token, &0);
+    client.create_package(&admin, &2, &recipient, &1_000, &token, &0);
+    assert_eq!(
+        client.try_create_package(&admin, &3, &admin, &1, &token, &0),
+        Err(Ok(Error::AllowanceExceeded))
+    );
+
+    // Redirecting a package needs the recipient's consent or the delay
+    let reason = String::from_str(&env, "typo");
+    assert_eq!(
+        client.try_reassign_recipient(&1, &admin, &reason, &false),
+        Err(Ok(Error::TimelockRequired))
+    );
+    let new_recipient = Address::generate(&env);
+    client.reassign_recipient(&1, &new_recipient, &reason, &true);
+
+    // Revoking frees the funds, but refunding them to the admin is queued
+    client.revoke(&2);
+    assert_eq!(
+        client.try_refund(&admin, &2),
+        Err(Ok(Error::TimelockRequired))
+    );
+    assert_eq!(
+        client.try_batch_refund(&admin, &Vec::from_array(&env, [2])),
+        Err(Ok(Error::TimelockRequired))
+    );
+    let op_id = client.queue_operation(&TimelockOp::Refund(Vec::from_array(&env, [2])));
+    env.ledger().set_timestamp(now + 2 * DELAY);
+    client.execute_operation(&op_id);
+    assert_eq!(token_client.balance(&admin), 1_000);
+
+    // Revoked admin packages do not hand the allowance back
+    assert_eq!(
+        client.try_create_package(&admin, &3, &admin, &1, &token, &0),
+        Err(Ok(Error::AllowanceExceeded))
+    );
+}
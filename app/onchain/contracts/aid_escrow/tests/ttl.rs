@@ -0,0 +1,140 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{AidEscrowClient, Error, Role};
+use common::setup_funded;
+use soroban_sdk::{
+    Address, Env, Vec,
+    testutils::{Address as _, Ledger},
+};
+
+const DAY: u32 = 17_280;
+
+/// Advances the ledger by `days`, keeping the contract instance alive.
+fn advance_days(env: &Env, client: &AidEscrowClient, admin: &Address, days: u32) {
+    for _ in 0..days / 20 {
+        env.ledger().with_mut(|li| li.sequence_number += 20 * DAY);
+        client.bump_instance(admin);
+    }
+}
+
+#[test]
+fn test_new_packages_get_full_ttl() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let seq = env.ledger().sequence();
+
+    client.create_package(&admin, &1, &recipient, &100, &token_client.address, &0);
+    let mut recipients = Vec::new(&env);
+    recipients.push_back(recipient.clone());
+    let mut amounts = Vec::new(&env);
+    amounts.push_back(200);
+    client.batch_create_packages(&admin, &recipients, &amounts, &token_client.address, &3600);
+
+    // Nothing is close to archival right after creation
+    assert_eq!(
+        client
+            .get_packages_near_archival(&0, &10, &(30 * DAY))
+            .records
+            .len(),
+        0
+    );
+
+    let page = client.get_packages_near_archival(&0, &10, &(120 * DAY));
+    assert_eq!(page.records.len(), 2);
+    assert_eq!(page.next_cursor, None);
+    for record in page.records.iter() {
+        assert_eq!(record.live_until, seq + 120 * DAY);
+    }
+    assert_eq!(page.records.get(0).unwrap().id, 1);
+
+    // Scans are paged like `list_packages`
+    let page = client.get_packages_near_archival(&0, &1, &(120 * DAY));
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.next_cursor, Some(1));
+    let page = client.get_packages_near_archival(&1, &0, &(120 * DAY));
+    assert_eq!(page.records.get(0).unwrap().id, 2);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_bump_packages_extends_near_archival() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let keeper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &100, &token_client.address, &0);
+    client.create_package(&admin, &2, &recipient, &100, &token_client.address, &0);
+    client.grant_role(&Role::Auditor, &keeper);
+
+    advance_days(&env, &client, &admin, 100);
+
+    let near = client
+        .get_packages_near_archival(&0, &10, &(30 * DAY))
+        .records;
+    assert_eq!(near.len(), 2);
+
+    // A keeper extends one of them; unknown ids are skipped
+    let mut ids = Vec::new(&env);
+    ids.push_back(1);
+    ids.push_back(99);
+    client.bump_packages(&keeper, &ids);
+
+    let near = client
+        .get_packages_near_archival(&0, &10, &(30 * DAY))
+        .records;
+    assert_eq!(near.len(), 1);
+    assert_eq!(near.get(0).unwrap().id, 2);
+    assert_eq!(client.get_package(&1).amount, 100);
+}
+
+#[test]
+fn test_touching_a_package_extends_it() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+
+    client.create_package(&admin, &1, &recipient, &100, &token_client.address, &0);
+    client.create_package(&admin, &2, &recipient, &100, &token_client.address, &0);
+
+    advance_days(&env, &client, &admin, 100);
+
+    // Revoking writes the package back and extends it; it is also no longer
+    // reported since only unclaimed packages are at risk
+    client.revoke(&1);
+    let near = client
+        .get_packages_near_archival(&0, &10, &(30 * DAY))
+        .records;
+    assert_eq!(near.len(), 1);
+    assert_eq!(near.get(0).unwrap().id, 2);
+
+    client.refund(&admin, &1);
+    assert_eq!(token_client.balance(&admin), 100);
+}
+
+#[test]
+fn test_bump_requires_admin_or_keeper() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _token_client, _admin) = setup_funded(&env);
+    let outsider = Address::generate(&env);
+
+    assert_eq!(
+        client.try_bump_instance(&outsider),
+        Err(Ok(Error::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_bump_packages(&outsider, &Vec::new(&env)),
+        Err(Ok(Error::NotAuthorized))
+    );
+}
@@ -0,0 +1,325 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{
+    AidEscrow, AidEscrowClient, BatchCreateResult, Error, PackageKind, PackageStatus, Role,
+};
+use common::setup_token;
+use soroban_sdk::{
+    Address, BytesN, Env, IntoVal, Map, String, Symbol, Val, Vec, testutils::Address as _,
+    token::TokenClient,
+};
+
+/// Helper: a package as the first release (v1) stored it, with only the
+/// fields that release had.
+fn legacy_package(
+    env: &Env,
+    id: u64,
+    recipient: &Address,
+    amount: i128,
+    token: &Address,
+    status: PackageStatus,
+) -> Map<Symbol, Val> {
+    let mut fields: Map<Symbol, Val> = Map::new(env);
+    fields.set(Symbol::new(env, "id"), id.into_val(env));
+    fields.set(Symbol::new(env, "recipient"), recipient.into_val(env));
+    fields.set(Symbol::new(env, "amount"), amount.into_val(env));
+    fields.set(Symbol::new(env, "token"), token.into_val(env));
+    fields.set(Symbol::new(env, "status"), (status as u32).into_val(env));
+    fields.set(
+        Symbol::new(env, "created_at"),
+        env.ledger().timestamp().into_val(env),
+    );
+    fields.set(Symbol::new(env, "expires_at"), 0u64.into_val(env));
+    fields.set(
+        Symbol::new(env, "metadata"),
+        Map::<Symbol, String>::new(env).into_val(env),
+    );
+    fields
+}
+
+/// Helper: a contract holding storage in the v1 (symbol key) layout, with
+/// packages of 400 for `recipient`: id 6 claimed and `open` unclaimed ones
+/// from id 7, plus one enabled and one disabled distributor. Returns client,
+/// token client and admin.
+fn setup_legacy(
+    env: &Env,
+    recipient: &Address,
+    open: u64,
+) -> (AidEscrowClient<'static>, TokenClient<'static>, Address) {
+    let admin = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let (token_client, token_admin_client) = setup_token(env, &token_admin);
+    let token = token_client.address.clone();
+
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(env, &contract_id);
+    token_admin_client.mint(&contract_id, &10_000);
+
+    env.as_contract(&contract_id, || {
+        let instance = env.storage().instance();
+        let persistent = env.storage().persistent();
+
+        let mut locked: Map<Address, i128> = Map::new(env);
+        locked.set(token.clone(), 400 * open as i128);
+        let mut distributors: Map<Address, bool> = Map::new(env);
+        distributors.set(admin.clone(), true);
+        distributors.set(Address::generate(env), false);
+
+        instance.set(&Symbol::new(env, "admin"), &admin);
+        instance.set(&Symbol::new(env, "locked"), &locked);
+        instance.set(&Symbol::new(env, "pkg_cnt"), &0u64);
+        instance.set(&Symbol::new(env, "pkg_idx"), &(open + 1));
+        instance.set(&Symbol::new(env, "dstrbtrs"), &distributors);
+        for idx in 0..=open {
+            let id = 6 + idx;
+            let status = if idx == 0 {
+                PackageStatus::Claimed
+            } else {
+                PackageStatus::Created
+            };
+            persistent.set(&(Symbol::new(env, "pidx"), idx), &id);
+            persistent.set(
+                &(Symbol::new(env, "pkg"), id),
+                &legacy_package(env, id, recipient, 400, &token, status),
+            );
+        }
+    });
+
+    (client, token_client, admin)
+}
+
+#[test]
+fn test_migrate_legacy_storage() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let recipient = Address::generate(&env);
+    let (client, token_client, admin) = setup_legacy(&env, &recipient, 1);
+
+    assert_eq!(client.version(), 1);
+    assert_eq!(client.try_get_admin(), Err(Ok(Error::NotInitialized)));
+
+    assert_eq!(client.migrate(&0, &10), None);
+
+    // Migration is authorized by the legacy admin
+    let auths = env.auths();
+    assert_eq!(auths[0].0, admin);
+
+    assert_eq!(client.version(), 8);
+    assert_eq!(client.get_admin(), admin);
+
+    // Enabled v1 distributors keep the role; disabled ones are dropped
+    let distributors = client.list_role_members(&Role::Distributor);
+    assert_eq!(distributors, Vec::from_array(&env, [admin.clone()]));
+
+    // Counters for legacy packages appear once rebuilt
+    assert_eq!(
+        client.get_aggregates(&token_client.address).total_committed,
+        0
+    );
+    assert_eq!(client.rebuild_aggregates(&admin, &0, &10), None);
+    let aggregates = client.get_aggregates(&token_client.address);
+    assert_eq!(aggregates.total_committed, 400);
+    assert_eq!(aggregates.committed_count, 1);
+    assert_eq!(aggregates.total_claimed, 400);
+    assert_eq!(aggregates.claimed_count, 1);
+
+    // Fields added since v1 are filled in
+    let package = client.get_package(&7);
+    assert_eq!(package.recipient, Some(recipient.clone()));
+    assert_eq!(package.claimed_amount, 0);
+    assert_eq!(package.campaign_id, None);
+    assert_eq!(package.created_by, admin);
+    assert_eq!(package.kind, PackageKind::Standard);
+    assert_eq!(package.previous_recipients.len(), 0);
+    assert_eq!(client.get_package(&6).claimed_amount, 400);
+
+    // Legacy packages are indexed for their recipient
+    let page = client.get_packages_for_recipient(&recipient, &0, &10);
+    assert_eq!(page.packages.len(), 2);
+    assert_eq!(page.packages.get(1).unwrap().id, 7);
+
+    // Legacy packages remain claimable under the new layout
+    client.claim(&7);
+    assert_eq!(client.get_package(&7).status, PackageStatus::Claimed);
+    assert_eq!(token_client.balance(&recipient), 400);
+
+    // Running it again is rejected
+    assert_eq!(client.try_migrate(&0, &10), Err(Ok(Error::InvalidState)));
+}
+
+#[test]
+fn test_rebuild_aggregates_after_migration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let recipient = Address::generate(&env);
+    let (client, token_client, admin) = setup_legacy(&env, &recipient, 1);
+    client.migrate(&0, &10);
+
+    // A legacy package changing state before the rebuild reaches it is
+    // counted once, in its final state
+    client.claim(&7);
+    assert_eq!(
+        client.get_aggregates(&token_client.address).claimed_count,
+        0
+    );
+
+    // A package created after migration is counted immediately
+    client.create_package(&admin, &8, &recipient, &100, &token_client.address, &0);
+    assert_eq!(
+        client.get_aggregates(&token_client.address).committed_count,
+        1
+    );
+
+    // Cursor must match the recorded progress
+    assert_eq!(
+        client.try_rebuild_aggregates(&admin, &1, &10),
+        Err(Ok(Error::InvalidState))
+    );
+    // A zero limit rebuilds a full batch rather than stalling
+    assert_eq!(client.rebuild_aggregates(&admin, &0, &0), None);
+
+    let aggregates = client.get_aggregates(&token_client.address);
+    assert_eq!(aggregates.total_claimed, 800);
+    assert_eq!(aggregates.claimed_count, 2);
+    assert_eq!(aggregates.total_committed, 100);
+    assert_eq!(aggregates.committed_count, 1);
+
+    // The rebuild only runs once
+    assert_eq!(
+        client.try_rebuild_aggregates(&admin, &1, &10),
+        Err(Ok(Error::InvalidState))
+    );
+}
+
+#[test]
+fn test_migrate_in_batches() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let recipient = Address::generate(&env);
+    let (client, token_client, admin) = setup_legacy(&env, &recipient, 4);
+
+    assert_eq!(client.migrate(&0, &2), Some(2));
+
+    // The contract stays paused until every package has been rewritten
+    assert_eq!(client.version(), 1);
+    assert_eq!(client.try_claim(&7), Err(Ok(Error::ContractPaused)));
+    assert_eq!(
+        client.try_rebuild_aggregates(&admin, &0, &10),
+        Err(Ok(Error::InvalidState))
+    );
+
+    // Cursor must match the recorded progress
+    assert_eq!(client.try_migrate(&1, &2), Err(Ok(Error::InvalidState)));
+    assert_eq!(client.migrate(&2, &2), Some(4));
+    // A zero limit migrates a full batch rather than stalling
+    assert_eq!(client.migrate(&4, &0), None);
+
+    assert_eq!(client.version(), 8);
+    assert_eq!(client.rebuild_aggregates(&admin, &0, &10), None);
+    assert_eq!(
+        client.get_aggregates(&token_client.address).committed_count,
+        4
+    );
+    for id in 7..11 {
+        client.claim(&id);
+    }
+    assert_eq!(token_client.balance(&recipient), 1_600);
+}
+
+#[test]
+fn test_batches_skip_legacy_ids() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    // v1 packages 6 and 7 sit ahead of the v1 counter
+    let recipient = Address::generate(&env);
+    let (client, token_client, admin) = setup_legacy(&env, &recipient, 1);
+    client.migrate(&0, &10);
+    let token = token_client.address.clone();
+
+    let mut recipients = Vec::new(&env);
+    let mut amounts = Vec::new(&env);
+    for _ in 0..4 {
+        recipients.push_back(Address::generate(&env));
+        amounts.push_back(100);
+    }
+    let ids = client.batch_create_packages(&admin, &recipients, &amounts, &token, &0);
+    assert_eq!(ids, Vec::from_array(&env, [0, 1, 2, 3]));
+
+    // A failed row does not hold up the rows after it
+    amounts.set(0, 0);
+    let rows: std::vec::Vec<u64> = client
+        .validate_batch(&admin, &recipients, &amounts, &token, &0)
+        .rows
+        .iter()
+        .map(|row| row.id)
+        .collect();
+    assert_eq!(rows, [4, 4, 5, 8]);
+    assert_eq!(
+        client.batch_create_best_effort(&admin, &recipients, &amounts, &token, &0),
+        Vec::from_array(
+            &env,
+            [
+                BatchCreateResult::Failed(Error::InvalidAmount as u32),
+                BatchCreateResult::Created(4),
+                BatchCreateResult::Created(5),
+                BatchCreateResult::Created(8),
+            ]
+        )
+    );
+    assert_eq!(client.get_package(&7).amount, 400);
+}
+
+#[test]
+fn test_init_rejected_on_legacy_instance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let recipient = Address::generate(&env);
+    let (client, _token_client, _admin) = setup_legacy(&env, &recipient, 1);
+    let attacker = Address::generate(&env);
+
+    assert_eq!(
+        client.try_init(&attacker),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
+
+#[test]
+fn test_new_deployment_is_current() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+
+    assert_eq!(client.version(), 8);
+    assert_eq!(client.try_migrate(&0, &10), Err(Ok(Error::InvalidState)));
+}
+
+#[test]
+fn test_upgrade_gated_by_council_and_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(AidEscrow, ());
+    let client = AidEscrowClient::new(&env, &contract_id);
+    client.init(&admin);
+    let hash = BytesN::from_array(&env, &[0u8; 32]);
+
+    client.set_timelock_delay(&3600);
+    assert_eq!(client.try_upgrade(&hash), Err(Ok(Error::TimelockRequired)));
+
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+    client.set_council(&signers, &1);
+    assert_eq!(client.try_upgrade(&hash), Err(Ok(Error::CouncilRequired)));
+}
@@ -0,0 +1,196 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{BatchCreateResult, Config, Error, PackageDiagnostics, Role};
+use common::setup_funded;
+use soroban_sdk::{Address, Env, Vec, testutils::Address as _};
+
+fn codes(env: &Env, errors: &[Error]) -> Vec<u32> {
+    let mut codes = Vec::new(env);
+    for error in errors {
+        codes.push_back(*error as u32);
+    }
+    codes
+}
+
+#[test]
+fn test_validate_package() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let token = token_client.address.clone();
+
+    let diagnostics = client.validate_package(&admin, &1, &500, &token, &0);
+    assert_eq!(
+        diagnostics,
+        PackageDiagnostics {
+            id: 1,
+            errors: Vec::new(&env),
+        }
+    );
+
+    // Validation does not change state
+    assert!(client.try_get_package(&1).is_err());
+    client.create_package(&admin, &1, &recipient, &500, &token, &0);
+
+    // Every failing check is reported, not just the first
+    client.set_config(
+        &admin,
+        &Config {
+            min_amount: 100,
+            max_expires_in: 0,
+            allowed_tokens: Vec::new(&env),
+        },
+    );
+    assert_eq!(
+        client.validate_package(&admin, &1, &50, &token, &0).errors,
+        codes(&env, &[Error::InvalidAmount, Error::PackageIdExists])
+    );
+    assert_eq!(
+        client
+            .validate_package(&admin, &2, &9_600, &token, &0)
+            .errors,
+        codes(&env, &[Error::InsufficientFunds])
+    );
+    assert_eq!(
+        client.try_create_package(&admin, &2, &recipient, &9_600, &token, &0),
+        Err(Ok(Error::InsufficientFunds))
+    );
+}
+
+#[test]
+fn test_validate_package_checks_operator() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let token = token_client.address.clone();
+    let distributor = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    client.grant_role(&Role::Distributor, &distributor);
+    client.set_distributor_allowance(&distributor, &token, &400, &0, &0);
+
+    assert!(
+        client
+            .validate_package(&distributor, &1, &400, &token, &0)
+            .errors
+            .is_empty()
+    );
+    assert_eq!(
+        client
+            .validate_package(&distributor, &1, &500, &token, &0)
+            .errors,
+        codes(&env, &[Error::AllowanceExceeded])
+    );
+    assert_eq!(
+        client
+            .validate_package(&outsider, &1, &100, &token, &0)
+            .errors,
+        codes(&env, &[Error::NotAuthorized, Error::AllowanceExceeded])
+    );
+
+    client.pause(&admin);
+    assert_eq!(
+        client.validate_package(&admin, &1, &100, &token, &0).errors,
+        codes(&env, &[Error::ContractPaused])
+    );
+}
+
+#[test]
+fn test_validate_batch_matches_creation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let token = token_client.address.clone();
+    let recipients = Vec::from_array(
+        &env,
+        [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ],
+    );
+    // The third row no longer fits once the first two are locked
+    let amounts = Vec::from_array(&env, [4_000, 0, 7_000, 6_000]);
+
+    let diagnostics = client.validate_batch(&admin, &recipients, &amounts, &token, &3600);
+    assert!(diagnostics.errors.is_empty());
+    let rows: std::vec::Vec<(u64, Vec<u32>)> = diagnostics
+        .rows
+        .iter()
+        .map(|row| (row.id, row.errors))
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            (0, Vec::new(&env)),
+            (1, codes(&env, &[Error::InvalidAmount])),
+            (1, codes(&env, &[Error::InsufficientFunds])),
+            (1, Vec::new(&env)),
+        ]
+    );
+
+    // A row fails, so the atomic batch would too
+    assert_eq!(
+        client.try_batch_create_packages(&admin, &recipients, &amounts, &token, &3600),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        client.batch_create_best_effort(&admin, &recipients, &amounts, &token, &3600),
+        Vec::from_array(
+            &env,
+            [
+                BatchCreateResult::Created(0),
+                BatchCreateResult::Failed(Error::InvalidAmount as u32),
+                BatchCreateResult::Failed(Error::InsufficientFunds as u32),
+                BatchCreateResult::Created(1),
+            ]
+        )
+    );
+}
+
+#[test]
+fn test_validate_batch_reports_batch_errors() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let token = token_client.address.clone();
+    let recipients = Vec::from_array(&env, [Address::generate(&env), Address::generate(&env)]);
+
+    let diagnostics = client.validate_batch(
+        &admin,
+        &recipients,
+        &Vec::from_array(&env, [100]),
+        &token,
+        &0,
+    );
+    assert_eq!(diagnostics.errors, codes(&env, &[Error::MismatchedArrays]));
+    assert!(diagnostics.rows.is_empty());
+
+    // Batch IDs are numbered past manually chosen ones
+    let amounts = Vec::from_array(&env, [100, 200]);
+    client.create_package(&admin, &0, &recipients.get(0).unwrap(), &100, &token, &0);
+    let diagnostics = client.validate_batch(&admin, &recipients, &amounts, &token, &0);
+    assert!(diagnostics.errors.is_empty());
+    let ids: std::vec::Vec<u64> = diagnostics.rows.iter().map(|row| row.id).collect();
+    assert_eq!(ids, [1, 2]);
+    assert_eq!(
+        client.batch_create_packages(&admin, &recipients, &amounts, &token, &0),
+        Vec::from_array(&env, [1, 2])
+    );
+
+    client.pause(&admin);
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client
+            .validate_batch(&outsider, &recipients, &amounts, &token, &0)
+            .errors,
+        codes(&env, &[Error::ContractPaused, Error::NotAuthorized])
+    );
+}
@@ -0,0 +1,167 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{Error, PackageStatus, VestingSchedule};
+use common::setup_funded;
+use soroban_sdk::{
+    Address, Env,
+    testutils::{Address as _, Ledger},
+};
+
+/// 1,000 over 1,000 seconds starting 100 seconds from now, with a 250 second cliff.
+fn schedule(env: &Env) -> VestingSchedule {
+    let now = env.ledger().timestamp();
+    VestingSchedule {
+        start: now + 100,
+        cliff: now + 350,
+        end: now + 1_100,
+    }
+}
+
+#[test]
+fn test_vesting_claims_accrue_linearly() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let sched = schedule(&env);
+
+    client.create_vesting_package(
+        &admin,
+        &1,
+        &recipient,
+        &1_000,
+        &token_client.address,
+        &sched,
+        &0,
+    );
+
+    // Nothing before the cliff
+    env.ledger().set_timestamp(sched.cliff - 1);
+    assert_eq!(client.claimable_now(&1), 0);
+    assert_eq!(client.try_claim(&1), Err(Ok(Error::NothingToClaim)));
+
+    // A quarter has vested at the cliff
+    env.ledger().set_timestamp(sched.cliff);
+    assert_eq!(client.claimable_now(&1), 250);
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 250);
+    assert_eq!(
+        client.get_package(&1).status,
+        PackageStatus::PartiallyClaimed
+    );
+    assert_eq!(client.claimable_now(&1), 0);
+
+    // Repeated claims pay out the vested delta
+    env.ledger().set_timestamp(sched.start + 600);
+    assert_eq!(client.claimable_now(&1), 350);
+    assert_eq!(
+        client.get_claimable_total(&recipient, &token_client.address),
+        350
+    );
+    assert_eq!(
+        client.try_claim_partial(&1, &351),
+        Err(Ok(Error::InvalidAmount))
+    );
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 600);
+
+    env.ledger().set_timestamp(sched.end + 10);
+    client.claim(&1);
+    assert_eq!(token_client.balance(&recipient), 1_000);
+    assert_eq!(client.get_package(&1).status, PackageStatus::Claimed);
+}
+
+#[test]
+fn test_revoke_vesting_returns_unvested_only() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let sched = schedule(&env);
+
+    client.create_vesting_package(
+        &admin,
+        &1,
+        &recipient,
+        &1_000,
+        &token_client.address,
+        &sched,
+        &0,
+    );
+
+    env.ledger().set_timestamp(sched.start + 400);
+    client.claim(&1);
+    env.ledger().set_timestamp(sched.start + 700);
+    client.revoke(&1);
+
+    // The recipient receives everything vested; the rest is back in the pool
+    assert_eq!(token_client.balance(&recipient), 700);
+    let pkg = client.get_package(&1);
+    assert_eq!(pkg.status, PackageStatus::Cancelled);
+    assert_eq!(pkg.claimed_amount, 700);
+
+    let agg = client.get_aggregates(&token_client.address);
+    assert_eq!(agg.total_claimed, 700);
+    assert_eq!(agg.total_expired_cancelled, 300);
+    assert_eq!(agg.total_committed, 0);
+
+    client.withdraw_surplus(&admin, &9_300, &token_client.address);
+    assert_eq!(client.claimable_now(&1), 0);
+}
+
+#[test]
+fn test_invalid_vesting_schedules() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let sched = schedule(&env);
+
+    let invalid = [
+        VestingSchedule {
+            end: sched.start,
+            ..sched.clone()
+        },
+        VestingSchedule {
+            cliff: sched.start - 1,
+            ..sched.clone()
+        },
+        VestingSchedule {
+            cliff: sched.end + 1,
+            ..sched.clone()
+        },
+    ];
+    for schedule in invalid.iter() {
+        assert_eq!(
+            client.try_create_vesting_package(
+                &admin,
+                &1,
+                &recipient,
+                &1_000,
+                &token_client.address,
+                schedule,
+                &0,
+            ),
+            Err(Ok(Error::InvalidState))
+        );
+    }
+
+    // Expiry before the schedule ends
+    assert_eq!(
+        client.try_create_vesting_package(
+            &admin,
+            &1,
+            &recipient,
+            &1_000,
+            &token_client.address,
+            &sched,
+            &(sched.end - 1),
+        ),
+        Err(Ok(Error::InvalidState))
+    );
+}
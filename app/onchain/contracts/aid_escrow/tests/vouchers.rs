@@ -0,0 +1,326 @@
+#![cfg(test)]
+
+mod common;
+
+use aid_escrow::{AidEscrowClient, Error, Role, Voucher};
+use common::setup_funded;
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    Address, BytesN, Env,
+    testutils::{Address as _, Ledger},
+    xdr::ToXdr,
+};
+
+/// Helper: registers a voucher signer and returns its signing key.
+fn setup_signer(env: &Env, client: &AidEscrowClient, operator: &Address) -> SigningKey {
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+    let public_key = BytesN::from_array(env, &key.verifying_key().to_bytes());
+    client.set_voucher_signer(operator, &public_key);
+    key
+}
+
+/// Helper: signs a voucher for the given contract the way the issuer would.
+fn sign(env: &Env, key: &SigningKey, client: &AidEscrowClient, voucher: &Voucher) -> BytesN<64> {
+    let message = (client.address.clone(), voucher.clone()).to_xdr(env);
+    let message: std::vec::Vec<u8> = message.iter().collect();
+    BytesN::from_array(env, &key.sign(&message).to_bytes())
+}
+
+fn voucher(
+    id: u64,
+    issuer: &Address,
+    recipient: &Address,
+    token: &Address,
+    amount: i128,
+) -> Voucher {
+    Voucher {
+        id,
+        issuer: issuer.clone(),
+        recipient: recipient.clone(),
+        token: token.clone(),
+        amount,
+        expires_at: 0,
+    }
+}
+
+#[test]
+fn test_redeem_voucher() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let key = setup_signer(&env, &client, &admin);
+    let recipient = Address::generate(&env);
+
+    assert_eq!(
+        client.fund_voucher_budget(&admin, &token_client.address, &1_000),
+        1_000
+    );
+
+    let v = voucher(1, &admin, &recipient, &token_client.address, 300);
+    client.redeem_voucher(&v, &sign(&env, &key, &client, &v));
+
+    assert_eq!(token_client.balance(&recipient), 300);
+    assert_eq!(
+        client.get_voucher_budget(&admin, &token_client.address),
+        700
+    );
+    assert!(client.is_voucher_spent(&admin, &1));
+
+    // Each voucher can be redeemed once
+    assert_eq!(
+        client.try_redeem_voucher(&v, &sign(&env, &key, &client, &v)),
+        Err(Ok(Error::VoucherSpent))
+    );
+}
+
+#[test]
+fn test_voucher_signature_checked() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let recipient = Address::generate(&env);
+    let v = voucher(1, &admin, &recipient, &token_client.address, 300);
+
+    // No signer registered yet
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+    assert_eq!(
+        client.try_redeem_voucher(&v, &sign(&env, &key, &client, &v)),
+        Err(Ok(Error::InvalidState))
+    );
+
+    let key = setup_signer(&env, &client, &admin);
+    client.fund_voucher_budget(&admin, &token_client.address, &1_000);
+
+    // A signature by another key is rejected
+    let other = SigningKey::from_bytes(&[8u8; 32]);
+    assert!(
+        client
+            .try_redeem_voucher(&v, &sign(&env, &other, &client, &v))
+            .is_err()
+    );
+
+    // Changing any field invalidates the signature
+    let signature = sign(&env, &key, &client, &v);
+    let tampered = Voucher {
+        amount: 900,
+        ..v.clone()
+    };
+    assert!(client.try_redeem_voucher(&tampered, &signature).is_err());
+    assert_eq!(token_client.balance(&recipient), 0);
+
+    client.redeem_voucher(&v, &signature);
+    assert_eq!(token_client.balance(&recipient), 300);
+}
+
+#[test]
+fn test_voucher_budget_limits_redemptions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let key = setup_signer(&env, &client, &admin);
+    let recipient = Address::generate(&env);
+
+    client.fund_voucher_budget(&admin, &token_client.address, &500);
+
+    // The budget is locked: packages cannot use it
+    let package_recipient = Address::generate(&env);
+    assert_eq!(
+        client.try_create_package(
+            &admin,
+            &1,
+            &package_recipient,
+            &9_600,
+            &token_client.address,
+            &0
+        ),
+        Err(Ok(Error::InsufficientFunds))
+    );
+
+    let v = voucher(1, &admin, &recipient, &token_client.address, 600);
+    assert_eq!(
+        client.try_redeem_voucher(&v, &sign(&env, &key, &client, &v)),
+        Err(Ok(Error::InsufficientFunds))
+    );
+    assert!(!client.is_voucher_spent(&admin, &1));
+
+    // Unused budget can be returned to the pool
+    assert_eq!(
+        client.release_voucher_budget(&admin, &token_client.address, &200),
+        300
+    );
+    client.create_package(
+        &admin,
+        &1,
+        &package_recipient,
+        &9_600,
+        &token_client.address,
+        &0,
+    );
+    assert_eq!(
+        client.try_release_voucher_budget(&admin, &token_client.address, &400),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_voucher_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let key = setup_signer(&env, &client, &admin);
+    let recipient = Address::generate(&env);
+    client.fund_voucher_budget(&admin, &token_client.address, &1_000);
+
+    let v = Voucher {
+        expires_at: env.ledger().timestamp() + 3600,
+        ..voucher(1, &admin, &recipient, &token_client.address, 300)
+    };
+    let signature = sign(&env, &key, &client, &v);
+
+    env.ledger().set_timestamp(v.expires_at + 1);
+    assert_eq!(
+        client.try_redeem_voucher(&v, &signature),
+        Err(Ok(Error::PackageExpired))
+    );
+}
+
+#[test]
+fn test_distributor_voucher_budget_uses_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, _admin) = setup_funded(&env);
+    let distributor = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    client.grant_role(&Role::Distributor, &distributor);
+    client.set_distributor_allowance(&distributor, &token_client.address, &400, &0, &0);
+
+    let public_key = BytesN::from_array(&env, &[1u8; 32]);
+    assert_eq!(
+        client.try_set_voucher_signer(&outsider, &public_key),
+        Err(Ok(Error::NotAuthorized))
+    );
+    setup_signer(&env, &client, &distributor);
+
+    assert_eq!(
+        client.try_fund_voucher_budget(&distributor, &token_client.address, &500),
+        Err(Ok(Error::AllowanceExceeded))
+    );
+    client.fund_voucher_budget(&distributor, &token_client.address, &400);
+    assert_eq!(
+        client.get_voucher_budget(&distributor, &token_client.address),
+        400
+    );
+    assert_eq!(
+        client
+            .get_distributor_allowance(&distributor, &token_client.address)
+            .unwrap()
+            .remaining,
+        0
+    );
+
+    // Releasing unused budget gives the allowance back
+    client.release_voucher_budget(&distributor, &token_client.address, &150);
+    assert_eq!(
+        client
+            .get_distributor_allowance(&distributor, &token_client.address)
+            .unwrap()
+            .remaining,
+        150
+    );
+    client.fund_voucher_budget(&distributor, &token_client.address, &150);
+}
+
+#[test]
+fn test_vouchers_tied_to_issuer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let token = token_client.address.clone();
+    let distributor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.grant_role(&Role::Distributor, &distributor);
+    client.set_distributor_allowance(&distributor, &token, &400, &0, &0);
+
+    let admin_key = setup_signer(&env, &client, &admin);
+    let key = SigningKey::from_bytes(&[9u8; 32]);
+    client.set_voucher_signer(
+        &distributor,
+        &BytesN::from_array(&env, &key.verifying_key().to_bytes()),
+    );
+    assert_ne!(
+        client.get_voucher_signer(&distributor),
+        client.get_voucher_signer(&admin)
+    );
+    client.fund_voucher_budget(&admin, &token, &5_000);
+    client.fund_voucher_budget(&distributor, &token, &400);
+
+    // A distributor's key cannot spend the admin's budget
+    let v = voucher(1, &admin, &recipient, &token, 1_000);
+    assert!(
+        client
+            .try_redeem_voucher(&v, &sign(&env, &key, &client, &v))
+            .is_err()
+    );
+
+    // Its own vouchers are paid from its own budget only
+    let v = voucher(2, &distributor, &recipient, &token, 1_000);
+    assert_eq!(
+        client.try_redeem_voucher(&v, &sign(&env, &key, &client, &v)),
+        Err(Ok(Error::InsufficientFunds))
+    );
+    let v = voucher(3, &distributor, &recipient, &token, 300);
+    client.redeem_voucher(&v, &sign(&env, &key, &client, &v));
+    assert_eq!(client.get_voucher_budget(&distributor, &token), 100);
+    assert_eq!(client.get_voucher_budget(&admin, &token), 5_000);
+
+    // Vouchers stop being redeemable once the issuer loses the role
+    client.revoke_role(&Role::Distributor, &distributor);
+    let v = voucher(4, &distributor, &recipient, &token, 100);
+    assert_eq!(
+        client.try_redeem_voucher(&v, &sign(&env, &key, &client, &v)),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    let v = voucher(5, &admin, &recipient, &token, 1_000);
+    client.redeem_voucher(&v, &sign(&env, &admin_key, &client, &v));
+    assert_eq!(token_client.balance(&recipient), 1_300);
+}
+
+#[test]
+fn test_voucher_ids_scoped_to_issuer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, token_client, admin) = setup_funded(&env);
+    let token = token_client.address.clone();
+    let distributor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.grant_role(&Role::Distributor, &distributor);
+    client.set_distributor_allowance(&distributor, &token, &400, &0, &0);
+
+    let admin_key = setup_signer(&env, &client, &admin);
+    let key = SigningKey::from_bytes(&[9u8; 32]);
+    client.set_voucher_signer(
+        &distributor,
+        &BytesN::from_array(&env, &key.verifying_key().to_bytes()),
+    );
+    client.fund_voucher_budget(&admin, &token, &1_000);
+    client.fund_voucher_budget(&distributor, &token, &400);
+
+    // Redeeming the distributor's voucher 1 does not spend the admin's
+    let v = voucher(1, &distributor, &distributor, &token, 1);
+    client.redeem_voucher(&v, &sign(&env, &key, &client, &v));
+    assert!(client.is_voucher_spent(&distributor, &1));
+    assert!(!client.is_voucher_spent(&admin, &1));
+
+    let v = voucher(1, &admin, &recipient, &token, 300);
+    client.redeem_voucher(&v, &sign(&env, &admin_key, &client, &v));
+    assert!(client.is_voucher_spent(&admin, &1));
+    assert_eq!(token_client.balance(&recipient), 300);
+}